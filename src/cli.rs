@@ -0,0 +1,582 @@
+use argh::FromArgs;
+
+use crate::database::DEFAULT_GRACE_MINUTES;
+
+#[derive(FromArgs)]
+#[argh(help_triggers("-h", "--help", "help"))]
+/// CLI to track usage times for pc and applications
+pub struct Params {
+    /// to get the current version number
+    #[argh(switch, short = 'v')]
+    pub version: bool,
+
+    /// param: <[on|off|switch]>. set counter status, Use one of the following options. deprecated, use `settings set state`
+    #[argh(option)]
+    pub state: Option<String>,
+
+    /// param: <digit>. change the number of days during which the data is kept day by day. deprecated, use `settings set storage`
+    #[argh(option)]
+    pub storage: Option<u16>,
+
+    /// param: <digit>. number of months during which the monthly totals of the older days are kept, 0 keeps them forever. deprecated, use `settings set archive`
+    #[argh(option)]
+    pub archive: Option<u16>,
+
+    /// param: <minutes>. stop counting the screen time after this idle time, 0 disables it. deprecated, use `settings set idle`
+    #[argh(option)]
+    pub idle: Option<u16>,
+
+    /// param: <[on|off]>. also stop counting the applications when idle. deprecated, use `settings set idle-apps`
+    #[argh(option)]
+    pub idle_apps: Option<String>,
+
+    /// param: <[on|off]>. also measure the time during which an application has the focused window. deprecated, use `settings set foreground`
+    #[argh(option)]
+    pub foreground: Option<String>,
+
+    /// param: <[local|zone]>. time zone in which the days start and end, zone is a name of the tz database like Europe/Paris. deprecated, use `settings set timezone`
+    #[argh(option)]
+    pub timezone: Option<String>,
+
+    /// param: <HH:MM>. hour at which the days start, the usage before it belongs to the previous day. deprecated, use `settings set day-start`
+    #[argh(option)]
+    pub day_start: Option<String>,
+
+    /// get the settings of this application. deprecated, use `settings get`
+    #[argh(switch, short = 's')]
+    pub settings: bool,
+
+    /// param: <app_name>. enables notification mode for an application. deprecated, use `notif add`
+    #[argh(option)]
+    pub add_notif: Option<String>,
+
+    /// param: <time>. indicates the time in minutes before a notification is sent. deprecated, use `notif add`
+    #[argh(option)]
+    pub notif_time: Option<u16>,
+
+    /// param: <minutes>. used with [--add-notif], send a reminder every time the application is used for these minutes past the limit. deprecated, use `notif add --repeat`
+    #[argh(option)]
+    pub notif_repeat: Option<u16>,
+
+    /// param: <digit>. used with [--add-notif], the reminders beyond this number are critical. deprecated, use `notif add --critical-after`
+    #[argh(option)]
+    pub notif_critical: Option<u16>,
+
+    /// param: <app_name>. removes notification functionality for an application. deprecated, use `notif del`
+    #[argh(option)]
+    pub del_notif: Option<String>,
+
+    /// displays the list of notifications. deprecated, use `notif list`
+    #[argh(switch)]
+    pub print_notif: bool,
+
+    /// param: <app_name>. add a process matcher to an application, used with [--matcher]. deprecated, use `matcher add`
+    #[argh(option)]
+    pub add_matcher: Option<String>,
+
+    /// param: <kind:pattern>. matcher of the processes, kind is one of [name|comm|cmdline|exe], comm and cmdline take a regex. deprecated, use `matcher add`
+    #[argh(option)]
+    pub matcher: Option<String>,
+
+    /// param: <app_name>. removes the matcher given by [--matcher] or all the matchers of an application. deprecated, use `matcher del`
+    #[argh(option)]
+    pub del_matcher: Option<String>,
+
+    /// displays the list of matchers. deprecated, use `matcher list`
+    #[argh(switch)]
+    pub print_matcher: bool,
+
+    /// param: <group_name>. add the applications given by [--group-apps] to a group, the group is created if needed. deprecated, use `group add`
+    #[argh(option)]
+    pub add_group: Option<String>,
+
+    /// param: <app_name,app_name,…>. applications of the group. deprecated, use `group add`
+    #[argh(option)]
+    pub group_apps: Option<String>,
+
+    /// param: <group_name>. removes the applications given by [--group-apps] from a group or the whole group. deprecated, use `group del`
+    #[argh(option)]
+    pub del_group: Option<String>,
+
+    /// displays the list of groups. deprecated, use `group list`
+    #[argh(switch)]
+    pub print_group: bool,
+
+    /// launch update. deprecated, use `track`
+    #[argh(switch, short = 'u')]
+    pub update: bool,
+
+    /// launch the tracking daemon, to be used instead of the cron timer. deprecated, use `track --daemon`
+    #[argh(switch)]
+    pub daemon: bool,
+
+    /// param: <seconds>. interval between two samples of the daemon (default: 10). deprecated, use `track --interval`
+    #[argh(option)]
+    pub interval: Option<u16>,
+
+    /// param: <app_name>. add a application. deprecated, use `app add`
+    #[argh(option)]
+    pub add: Option<String>,
+
+    /// param: <app_name>. stop tracking an application, its history is kept. deprecated, use `app del`
+    #[argh(option)]
+    pub del: Option<String>,
+
+    /// param: <file>. export the usage of the days given by [--date] and [-n], all of them by default, - writes to the standard output. deprecated, use `export`
+    #[argh(option)]
+    pub export: Option<String>,
+
+    /// param: <file>. import the usage of a file in the format given by [--format], - reads the standard input. deprecated, use `import`
+    #[argh(option)]
+    pub import: Option<String>,
+
+    /// param: <[csv|json|ndjson|activitywatch|arbtt]>. format of the export or of the import, csv by default. activitywatch and arbtt are only imported. deprecated, use `export --format`
+    #[argh(option)]
+    pub format: Option<String>,
+
+    /// param: <[add|max|replace]>. how the imported time is merged with the time of the same day, add by default. deprecated, use `import --merge`
+    #[argh(option)]
+    pub merge: Option<String>,
+
+    /// displays what the import would change without changing anything. deprecated, use `import --dry-run`
+    #[argh(switch)]
+    pub dry_run: bool,
+
+    /// to retrieve data either for a day's info with [daydata], the hours of use of a day with [timeline], an application's or a group's info with [app-<name>] or the runs of an application with [sessions-<name>]. deprecated, use `query`
+    #[argh(option, short = 'q')]
+    pub query: Option<String>,
+
+    /// param: <[plain|table|json|csv]>. format of the printed data for the queries, the settings, the notifications and the groups, plain by default
+    #[argh(option)]
+    pub output: Option<String>,
+
+    /// param: <date>. select the date of the retrieved data, foramt : YYYY-mm-dd. you can also use keywords such as yesterday, last_week or a day of the week (monday…).. deprecated, use `query --date`
+    #[argh(option)]
+    pub date: Option<String>,
+
+    /// param: <digit>. select the number of day of the retrieved data. deprecated, use `query -n`
+    #[argh(option, short = 'n', default = "0")]
+    pub number: u16,
+
+    /// inverts the result for an application. deprecated, use `query -r`
+    #[argh(switch, short = 'r')]
+    pub reverse: bool,
+
+    /// displays the retrieved times with their seconds. deprecated, use `query --seconds`
+    #[argh(switch)]
+    pub seconds: bool,
+
+    /// color of tui. Choice : Black, Red, Green, Yellow, Blue, Magenta, Cyan, Gray, DarkGray, LightRed, LightGreen, LightYellow, LightBlue, LightMagenta, LightCyan, White. deprecated, use `tui -c`
+    #[argh(option, short = 'c')]
+    pub color: Option<String>,
+
+    /// launch tui. deprecated, use `tui`
+    #[argh(switch, short = 't')]
+    pub tui: bool,
+
+    #[argh(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    Track(Track),
+    App(AppCommand),
+    Matcher(MatcherCommand),
+    Group(GroupCommand),
+    Notif(NotifCommand),
+    Query(Query),
+    Settings(SettingsCommand),
+    Export(Export),
+    Import(Import),
+    Tui(Tui),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "track")]
+/// count the time of the pc and of the applications once, to be run every minute by a cron timer
+pub struct Track {
+    /// keep running and sample every [--interval] seconds instead of the cron timer
+    #[argh(switch)]
+    pub daemon: bool,
+
+    /// param: <seconds>. interval between two samples of the daemon (default: 10)
+    #[argh(option)]
+    pub interval: Option<u16>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "app")]
+/// add, stop tracking or list the applications
+pub struct AppCommand {
+    #[argh(subcommand)]
+    pub action: AppAction,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum AppAction {
+    Add(AppAdd),
+    Del(AppDel),
+    List(AppList),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "add")]
+/// track an application
+pub struct AppAdd {
+    /// name of the application
+    #[argh(positional)]
+    pub name: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "del")]
+/// stop tracking an application, its history is kept
+pub struct AppDel {
+    /// name of the application
+    #[argh(positional)]
+    pub name: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list")]
+/// display the tracked applications
+pub struct AppList {
+    /// param: <[plain|table|json|csv]>. format of the printed data, plain by default
+    #[argh(option)]
+    pub output: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "matcher")]
+/// add, remove or list the process matchers of the applications
+pub struct MatcherCommand {
+    #[argh(subcommand)]
+    pub action: MatcherAction,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum MatcherAction {
+    Add(MatcherAdd),
+    Del(MatcherDel),
+    List(MatcherList),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "add")]
+/// add a process matcher to an application
+pub struct MatcherAdd {
+    /// name of the application
+    #[argh(positional)]
+    pub name: String,
+
+    /// matcher of the processes as kind:pattern, kind is one of [name|comm|cmdline|exe], comm and cmdline take a regex
+    #[argh(positional)]
+    pub matcher: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "del")]
+/// remove a matcher or all the matchers of an application
+pub struct MatcherDel {
+    /// name of the application
+    #[argh(positional)]
+    pub name: String,
+
+    /// matcher to remove as kind:pattern
+    #[argh(positional)]
+    pub matcher: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list")]
+/// display the matchers
+pub struct MatcherList {
+    /// param: <[plain|table|json|csv]>. format of the printed data, plain by default
+    #[argh(option)]
+    pub output: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "group")]
+/// add, remove or list the groups of applications
+pub struct GroupCommand {
+    #[argh(subcommand)]
+    pub action: GroupAction,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum GroupAction {
+    Add(GroupAdd),
+    Del(GroupDel),
+    List(GroupList),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "add")]
+/// add applications to a group, the group is created if needed
+pub struct GroupAdd {
+    /// name of the group
+    #[argh(positional)]
+    pub name: String,
+
+    /// applications of the group
+    #[argh(positional)]
+    pub apps: Vec<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "del")]
+/// remove applications from a group or the whole group
+pub struct GroupDel {
+    /// name of the group
+    #[argh(positional)]
+    pub name: String,
+
+    /// applications to remove, the whole group is removed without any
+    #[argh(positional)]
+    pub apps: Vec<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list")]
+/// display the groups
+pub struct GroupList {
+    /// param: <[plain|table|json|csv]>. format of the printed data, plain by default
+    #[argh(option)]
+    pub output: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "notif")]
+/// add, remove or list the notifications
+pub struct NotifCommand {
+    #[argh(subcommand)]
+    pub action: NotifAction,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum NotifAction {
+    Add(NotifAdd),
+    Del(NotifDel),
+    List(NotifList),
+    Snooze(NotifSnooze),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "add")]
+/// send a notification when an application has been used for some time, an application can have several limits
+pub struct NotifAdd {
+    /// name of the application
+    #[argh(positional)]
+    pub name: String,
+
+    /// time in minutes before the notification is sent, it replaces the limit of the application with the same time
+    #[argh(positional)]
+    pub minutes: u16,
+
+    /// param: <days>. days on which the limit applies, a list separated by commas of days like mon, or weekdays, weekends, all by default
+    #[argh(option)]
+    pub days: Option<String>,
+
+    /// the minutes are counted over the week, from Monday, instead of the day
+    #[argh(switch)]
+    pub week: bool,
+
+    /// param: <minutes>. send a warning this number of minutes before the limit
+    #[argh(option, default = "0")]
+    pub warning: u16,
+
+    /// param: <minutes>. send a reminder every time the application is used for these minutes past the limit
+    #[argh(option, default = "0")]
+    pub repeat: u16,
+
+    /// param: <digit>. the reminders beyond this number are sent with a critical urgency
+    #[argh(option)]
+    pub critical_after: Option<u16>,
+
+    /// param: <text>. text of the notification instead of the default one
+    #[argh(option)]
+    pub message: Option<String>,
+
+    /// terminate the processes of the application when it is still used past the limit, after a warning and the grace period
+    #[argh(switch)]
+    pub kill: bool,
+
+    /// param: <command>. run this shell command instead when the application is still used past the limit, it must return
+    #[argh(option)]
+    pub command: Option<String>,
+
+    /// param: <minutes>. time between the warning and the action, 5 by default
+    #[argh(option, default = "DEFAULT_GRACE_MINUTES")]
+    pub grace: u16,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "del")]
+/// remove a limit of an application, or all of them without a time
+pub struct NotifDel {
+    /// name of the application
+    #[argh(positional)]
+    pub name: String,
+
+    /// time in minutes of the limits to remove, whatever their days and period without [--days] or [--week]
+    #[argh(positional)]
+    pub minutes: Option<u16>,
+
+    /// param: <days>. only remove the limit on these days, all by default when [--week] is given
+    #[argh(option)]
+    pub days: Option<String>,
+
+    /// only remove the limit counted over the week
+    #[argh(switch)]
+    pub week: bool,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "snooze")]
+/// suspend the actions of the limits of an application for today
+pub struct NotifSnooze {
+    /// name of the application
+    #[argh(positional)]
+    pub name: String,
+
+    /// reason of the snooze, it is kept in the database
+    #[argh(positional)]
+    pub reason: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list")]
+/// display the notifications
+pub struct NotifList {
+    /// param: <[plain|table|json|csv]>. format of the printed data, plain by default
+    #[argh(option)]
+    pub output: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "query")]
+/// retrieve the data of a day with [day], the hours of use of a day with [timeline], an application's or a group's info with [app <name>] or the runs of an application with [sessions <name>]
+pub struct Query {
+    /// kind of data among [day|timeline|app|sessions]
+    #[argh(positional)]
+    pub kind: String,
+
+    /// name of the application or of the group for [app] and [sessions]
+    #[argh(positional)]
+    pub name: Option<String>,
+
+    /// param: <date>. select the date of the retrieved data, format : YYYY-mm-dd. you can also use keywords such as yesterday, last_week or a day of the week (monday…).
+    #[argh(option)]
+    pub date: Option<String>,
+
+    /// param: <digit>. select the number of day of the retrieved data
+    #[argh(option, short = 'n', default = "0")]
+    pub number: u16,
+
+    /// inverts the result
+    #[argh(switch, short = 'r')]
+    pub reverse: bool,
+
+    /// displays the retrieved times with their seconds
+    #[argh(switch)]
+    pub seconds: bool,
+
+    /// param: <[plain|table|json|csv]>. format of the printed data, plain by default
+    #[argh(option)]
+    pub output: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "settings")]
+/// display or change the settings
+pub struct SettingsCommand {
+    #[argh(subcommand)]
+    pub action: SettingsAction,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum SettingsAction {
+    Get(SettingsGet),
+    Set(SettingsSet),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "get")]
+/// display the settings
+pub struct SettingsGet {
+    /// param: <[plain|table|json|csv]>. format of the printed data, plain by default
+    #[argh(option)]
+    pub output: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set")]
+/// change a setting among state <[on|off|switch]>, storage <days>, archive <months>, idle <minutes>, idle-apps <[on|off]>, foreground <[on|off]>, timezone <[local|zone]> and day-start <HH:MM>
+pub struct SettingsSet {
+    /// name of the setting
+    #[argh(positional)]
+    pub attribute: String,
+
+    /// new value of the setting
+    #[argh(positional)]
+    pub value: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "export")]
+/// export the usage of the days given by [--date] and [-n], all of them by default
+pub struct Export {
+    /// file to write, - writes to the standard output
+    #[argh(positional)]
+    pub path: String,
+
+    /// param: <[csv|json|ndjson]>. format of the export, csv by default
+    #[argh(option)]
+    pub format: Option<String>,
+
+    /// param: <date>. last day exported, format : YYYY-mm-dd or a keyword such as yesterday
+    #[argh(option)]
+    pub date: Option<String>,
+
+    /// param: <digit>. number of days exported
+    #[argh(option, short = 'n', default = "0")]
+    pub number: u16,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "import")]
+/// import the usage of a file
+pub struct Import {
+    /// file to read, - reads the standard input
+    #[argh(positional)]
+    pub path: String,
+
+    /// param: <[csv|json|ndjson|activitywatch|arbtt]>. format of the file, csv by default
+    #[argh(option)]
+    pub format: Option<String>,
+
+    /// param: <[add|max|replace]>. how the imported time is merged with the time of the same day, add by default
+    #[argh(option)]
+    pub merge: Option<String>,
+
+    /// displays what the import would change without changing anything
+    #[argh(switch)]
+    pub dry_run: bool,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tui")]
+/// launch tui
+pub struct Tui {
+    /// color of tui. Choice : Black, Red, Green, Yellow, Blue, Magenta, Cyan, Gray, DarkGray, LightRed, LightGreen, LightYellow, LightBlue, LightMagenta, LightCyan, White
+    #[argh(option, short = 'c')]
+    pub color: Option<String>,
+}
@@ -0,0 +1,112 @@
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::database::{Database, Increments, Tracked, update_values};
+use crate::error::Result;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+const SLEEP_STEP: Duration = Duration::from_millis(250);
+
+pub fn start(database: Database, interval: u16) -> Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(SIGTERM, Arc::clone(&stop))?;
+    signal_hook::flag::register(SIGINT, Arc::clone(&stop))?;
+
+    Daemon::new(database, interval, stop)?.run()
+}
+
+struct Daemon {
+    database: Database,
+    interval: u16,
+    stop: Arc<AtomicBool>,
+    tracked: Tracked,
+    // Seconds counted since the last flush that have not yet been written to the database
+    seconds: Increments,
+}
+
+impl Daemon {
+    fn new(database: Database, interval: u16, stop: Arc<AtomicBool>) -> Result<Daemon> {
+        let tracked = database.get_tracked()?;
+        Ok(Daemon {
+            database,
+            interval,
+            stop,
+            seconds: Increments::new(&tracked),
+            tracked,
+        })
+    }
+
+    // Only the setup is fatal, an error of the database such as a write of another process
+    // is reported and the seconds counted are kept until a flush succeeds
+    fn run(&mut self) -> Result<()> {
+        let mut last_flush = Instant::now();
+
+        while self.wait() {
+            // A sample that fails is skipped, the next one may succeed
+            if let Err(e) = self.sample() {
+                eprintln!("Error : {}", e);
+            }
+
+            if last_flush.elapsed() >= FLUSH_INTERVAL {
+                match self.flush() {
+                    Ok(()) => last_flush = Instant::now(),
+                    Err(e) => eprintln!("Error : {}", e),
+                }
+            }
+        }
+
+        self.flush()
+    }
+
+    fn sample(&mut self) -> Result<()> {
+        if self.database.is_enabled()? {
+            let counting = self.database.counting()?;
+            update_values(
+                &self.tracked,
+                &mut self.seconds,
+                self.interval as u64,
+                counting,
+            )?;
+        }
+        Ok(())
+    }
+
+    // Sleeps for one interval, returns false if a termination signal was received meanwhile
+    fn wait(&self) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(self.interval as u64);
+        while Instant::now() < deadline {
+            if self.stop.load(Ordering::Relaxed) {
+                return false;
+            }
+            thread::sleep(SLEEP_STEP.min(deadline - Instant::now()));
+        }
+
+        !self.stop.load(Ordering::Relaxed)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.database.is_enabled()? {
+            self.database.apply_retention()?;
+            let date = self.database.record_time(&self.tracked, &self.seconds)?;
+            // Once the time is recorded, a notification that cannot be sent or a limit that
+            // cannot be enforced must not count it again at the next flush
+            if let Err(e) = self
+                .database
+                .check_limits(&self.tracked, &self.seconds, date)
+            {
+                eprintln!("Error : {}", e);
+            }
+        }
+
+        // The seconds are written, the applications may have been added or removed while the
+        // daemon was running
+        self.seconds = Increments::new(&self.tracked);
+        self.tracked = self.database.get_tracked()?;
+        self.seconds = Increments::new(&self.tracked);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,12 @@
+use chrono::Utc;
+use regex::Regex;
 use std::env;
-use std::process::Command;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use super::focus::focused_pid;
+use crate::error::{Error, Result};
 
 pub const SCREENTIME: &str = "pc";
 
@@ -18,56 +25,264 @@ pub fn get_path_bdd() -> String {
     path
 }
 
-pub fn app_running(name: &str) -> bool {
-    if name == SCREENTIME {
-        return true;
+// A running process as seen in /proc. The kernel truncates `comm` to 15 characters,
+// so the basenames of the executable and of argv[0] are also kept in `names`.
+pub struct Process {
+    pid: u32,
+    uid: u32,
+    comm: String,
+    exe: Option<PathBuf>,
+    cmdline: String,
+    names: Vec<String>,
+}
+
+impl Process {
+    // A process can end while it is read, its files are then simply missing
+    fn read(pid: u32) -> Option<Process> {
+        let path = Path::new("/proc").join(pid.to_string());
+
+        let uid = fs::metadata(&path).ok()?.uid();
+        let comm = fs::read_to_string(path.join("comm")).ok()?;
+        let comm = comm.trim_end_matches('\n').to_string();
+        let exe = fs::read_link(path.join("exe")).ok();
+        let cmdline = fs::read(path.join("cmdline")).unwrap_or_default();
+
+        let mut names = vec![comm.clone()];
+        if let Some(name) = exe
+            .as_ref()
+            .and_then(|e| e.file_name())
+            .and_then(|n| n.to_str())
+        {
+            names.push(name.to_string());
+        }
+
+        let argv0 = cmdline.split(|b| *b == 0).next().unwrap_or_default();
+        let argv0 = String::from_utf8_lossy(argv0);
+        if let Some(name) = Path::new(argv0.as_ref())
+            .file_name()
+            .and_then(|n| n.to_str())
+        {
+            names.push(name.to_string());
+        }
+
+        let cmdline = cmdline
+            .split(|b| *b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Some(Process {
+            pid,
+            uid,
+            comm,
+            exe,
+            cmdline,
+            names,
+        })
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+}
+
+// Processes running at the time of the scan
+pub struct ProcessTable {
+    processes: Vec<Process>,
+}
+
+impl ProcessTable {
+    pub fn scan() -> Result<ProcessTable> {
+        let mut processes = Vec::new();
+
+        let entries = fs::read_dir("/proc").map_err(Error::Probe)?;
+        for entry in entries.flatten() {
+            let pid = entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.parse::<u32>().ok());
+            if let Some(process) = pid.and_then(Process::read) {
+                processes.push(process);
+            }
+        }
+
+        Ok(ProcessTable { processes })
+    }
+
+    pub fn matching<'a>(&'a self, app: &'a TrackedApp) -> impl Iterator<Item = &'a Process> {
+        self.processes.iter().filter(|p| app.matches(p))
+    }
+}
+
+pub enum Matcher {
+    Name(String),
+    Comm(Regex),
+    Cmdline(Regex),
+    Exe(PathBuf),
+}
+
+pub const MATCHER_KINDS: [&str; 4] = ["name", "comm", "cmdline", "exe"];
+
+impl Matcher {
+    // An empty regex matches every process, so no pattern may be empty
+    pub fn new(kind: &str, pattern: &str) -> std::result::Result<Matcher, String> {
+        if pattern.trim().is_empty() {
+            return Err(String::from("the pattern cannot be empty"));
+        }
+
+        match kind {
+            "name" => Ok(Matcher::Name(pattern.to_string())),
+            "comm" => Regex::new(pattern)
+                .map(Matcher::Comm)
+                .map_err(|e| e.to_string()),
+            "cmdline" => Regex::new(pattern)
+                .map(Matcher::Cmdline)
+                .map_err(|e| e.to_string()),
+            "exe" => Ok(Matcher::Exe(PathBuf::from(pattern))),
+            _ => Err(format!(
+                "unknown matcher kind {}, use one of [{}]",
+                kind,
+                MATCHER_KINDS.join("|")
+            )),
+        }
     }
 
-    let output = Command::new("pgrep")
-        .arg("-x")
-        .arg(name)
-        .output()
-        .expect("Failed to execute pgrep command");
+    fn matches(&self, process: &Process) -> bool {
+        match self {
+            Matcher::Name(name) => process.names.contains(name),
+            Matcher::Comm(regex) => regex.is_match(&process.comm),
+            Matcher::Cmdline(regex) => regex.is_match(&process.cmdline),
+            Matcher::Exe(path) => process.exe.as_ref() == Some(path),
+        }
+    }
+}
 
-    !output.stdout.is_empty()
+// An application followed by the tracker. Without any matcher, it is matched by the
+// processes whose name is its display name.
+pub struct TrackedApp {
+    pub name: String,
+    pub matchers: Vec<Matcher>,
 }
 
-pub fn update_values(names: &[String], values: &mut [u16]) {
-    let mut index = 0;
-    while index < names.len() {
-        if app_running(&names[index]) {
-            values[index] += 1;
+impl TrackedApp {
+    fn matches(&self, process: &Process) -> bool {
+        if self.matchers.is_empty() {
+            return process.names.contains(&self.name);
         }
 
-        index += 1;
+        self.matchers.iter().any(|m| m.matches(process))
     }
 }
 
-pub fn format_query(column_names: Vec<String>, values: Vec<u16>) -> String {
-    let mut names_query = String::new();
-    let mut values_query = String::new();
+// A group of applications whose time is the time during which any of them was running
+pub struct TrackedGroup {
+    pub name: String,
+    // Indices of the members in the list of tracked applications
+    pub members: Vec<usize>,
+}
+
+pub struct Tracked {
+    pub apps: Vec<TrackedApp>,
+    pub groups: Vec<TrackedGroup>,
+}
+
+// Whether the screen time and the applications are counted during this update, and
+// whether the focused window is looked up to count the foreground time
+#[derive(Clone, Copy)]
+pub struct Counting {
+    pub pc: bool,
+    pub apps: bool,
+    pub foreground: bool,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Increment {
+    pub running: u64,
+    pub foreground: u64,
+    // Unix timestamps bounding the updates during which the application was running
+    pub first_seen: Option<i64>,
+    pub last_seen: Option<i64>,
+}
+
+// Time counted for each tracked application and group, in the same order
+pub struct Increments {
+    pub apps: Vec<Increment>,
+    pub groups: Vec<Increment>,
+}
 
-    let mut names: Vec<String> = Vec::new();
-    for mut n in column_names {
-        n.insert(0, '[');
-        n.push(']');
-        names.push(n);
+impl Increments {
+    pub fn new(tracked: &Tracked) -> Increments {
+        Increments {
+            apps: vec![Increment::default(); tracked.apps.len()],
+            groups: vec![Increment::default(); tracked.groups.len()],
+        }
+    }
+}
+
+pub fn app_running(processes: &ProcessTable, app: &TrackedApp) -> bool {
+    if app.name == SCREENTIME {
+        return true;
     }
 
-    let mut index = 0;
-    while index < names.len() {
-        names_query.push_str(&names[index]);
-        names_query.push(',');
+    processes.matching(app).next().is_some()
+}
+
+pub fn update_values(
+    tracked: &Tracked,
+    values: &mut Increments,
+    step: u64,
+    counting: Counting,
+) -> Result<()> {
+    let processes = ProcessTable::scan()?;
+    let focused = if counting.foreground {
+        focused_pid().and_then(Process::read)
+    } else {
+        None
+    };
 
-        values_query.push_str(&(values[index].to_string()));
-        values_query.push(',');
+    let now = Utc::now().timestamp();
+    let mut running = vec![false; tracked.apps.len()];
+    let mut foreground = vec![false; tracked.apps.len()];
+    for (i, app) in tracked.apps.iter().enumerate() {
+        let counted = match app.name.as_str() {
+            SCREENTIME => counting.pc,
+            _ => counting.apps,
+        };
 
-        index += 1;
+        running[i] = counted && app_running(&processes, app);
+        foreground[i] =
+            counted && app.name != SCREENTIME && focused.as_ref().is_some_and(|p| app.matches(p));
+
+        if running[i] {
+            values.apps[i].running += step;
+            values.apps[i].first_seen.get_or_insert(now - step as i64);
+            values.apps[i].last_seen = Some(now);
+        }
+
+        if foreground[i] {
+            values.apps[i].foreground += step;
+        }
     }
 
-    names_query.pop();
-    values_query.pop();
+    // A group is counted once per update, however many of its members are running
+    for (group, value) in tracked.groups.iter().zip(values.groups.iter_mut()) {
+        if group.members.iter().any(|&i| running[i]) {
+            value.running += step;
+        }
 
-    format!("INSERT INTO time (date, {}) VALUES (CURRENT_DATE, {})", names_query, values_query)
+        if group.members.iter().any(|&i| foreground[i]) {
+            value.foreground += step;
+        }
+    }
+
+    Ok(())
 }
 
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
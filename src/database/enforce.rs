@@ -0,0 +1,68 @@
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Time left to the processes to exit after SIGTERM before they are killed
+const KILL_DELAY: Duration = Duration::from_secs(10);
+const POLL_STEP: Duration = Duration::from_millis(250);
+
+// Asks the processes to terminate, the ones still running after KILL_DELAY are killed
+pub fn terminate(pids: &[u32]) -> io::Result<()> {
+    if pids.is_empty() {
+        return Ok(());
+    }
+
+    signal("TERM", pids)?;
+    let deadline = Instant::now() + KILL_DELAY;
+    let mut running = pids.to_vec();
+    while !running.is_empty() && Instant::now() < deadline {
+        thread::sleep(POLL_STEP);
+        running.retain(is_running);
+    }
+
+    if !running.is_empty() {
+        signal("KILL", &running)?;
+    }
+    Ok(())
+}
+
+// The owner of this process, only its own processes are closed
+pub fn current_uid() -> io::Result<u32> {
+    Ok(fs::metadata("/proc/self")?.uid())
+}
+
+fn is_running(pid: &u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+// The processes that ended in the meantime also make kill fail, so a failure is only
+// an error when one of the processes is still running
+fn signal(name: &str, pids: &[u32]) -> io::Result<()> {
+    let status = Command::new("kill")
+        .arg(format!("-{}", name))
+        .args(pids.iter().map(u32::to_string))
+        .status()?;
+    if !status.success() && pids.iter().any(is_running) {
+        return Err(io::Error::other(format!(
+            "unable to send SIG{} to the processes {:?}",
+            name, pids
+        )));
+    }
+    Ok(())
+}
+
+// The command is run by the shell and must return, the update waits for it
+pub fn run_command(command: &str) -> io::Result<()> {
+    let status = Command::new("sh").arg("-c").arg(command).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "the command {} failed with {}",
+            command, status
+        )));
+    }
+    Ok(())
+}
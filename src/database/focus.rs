@@ -0,0 +1,99 @@
+use serde_json::Value;
+use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::Command;
+
+const SWAY_IPC_MAGIC: &[u8] = b"i3-ipc";
+const SWAY_IPC_GET_TREE: u32 = 4;
+
+// Pid of the process owning the focused window. The compositor given by the environment
+// is asked first: Hyprland, then sway, then the X11 window manager.
+pub fn focused_pid() -> Option<u32> {
+    if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        return hyprland_focused_pid();
+    }
+
+    if env::var_os("SWAYSOCK").is_some() {
+        return sway_focused_pid();
+    }
+
+    x11_focused_pid()
+}
+
+fn hyprland_focused_pid() -> Option<u32> {
+    let signature = env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+
+    // The socket moved from /tmp to the runtime directory in recent versions
+    let mut paths = Vec::new();
+    if let Ok(runtime_dir) = env::var("XDG_RUNTIME_DIR") {
+        paths.push(PathBuf::from(runtime_dir).join("hypr"));
+    }
+    paths.push(PathBuf::from("/tmp/hypr"));
+
+    let mut stream = paths
+        .iter()
+        .find_map(|path| UnixStream::connect(path.join(&signature).join(".socket.sock")).ok())?;
+    stream.write_all(b"activewindow").ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+
+    response
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("pid: "))
+        .and_then(|pid| pid.parse::<u32>().ok())
+}
+
+fn sway_focused_pid() -> Option<u32> {
+    let mut stream = UnixStream::connect(env::var("SWAYSOCK").ok()?).ok()?;
+
+    let mut request = SWAY_IPC_MAGIC.to_vec();
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    request.extend_from_slice(&SWAY_IPC_GET_TREE.to_ne_bytes());
+    stream.write_all(&request).ok()?;
+
+    let mut header = [0; 14];
+    stream.read_exact(&mut header).ok()?;
+    let length = u32::from_ne_bytes(header[6..10].try_into().ok()?) as usize;
+
+    let mut payload = vec![0; length];
+    stream.read_exact(&mut payload).ok()?;
+
+    let tree: Value = serde_json::from_slice(&payload).ok()?;
+    sway_find_focused(&tree)
+}
+
+fn sway_find_focused(node: &Value) -> Option<u32> {
+    if node["focused"].as_bool() == Some(true) {
+        return node["pid"].as_u64().map(|pid| pid as u32);
+    }
+
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[key].as_array())
+        .flatten()
+        .find_map(sway_find_focused)
+}
+
+fn x11_focused_pid() -> Option<u32> {
+    env::var_os("DISPLAY")?;
+
+    // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007
+    let window = xprop(&["-root", "_NET_ACTIVE_WINDOW"])?;
+    let window = window.split_whitespace().last()?.to_string();
+
+    // _NET_WM_PID(CARDINAL) = 1234
+    let pid = xprop(&["-id", &window, "_NET_WM_PID"])?;
+    pid.split_whitespace().last()?.parse::<u32>().ok()
+}
+
+fn xprop(args: &[&str]) -> Option<String> {
+    let output = Command::new("xprop").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
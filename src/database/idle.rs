@@ -0,0 +1,80 @@
+use chrono::Utc;
+use std::env;
+use std::fs;
+use std::process::Command;
+use std::time::SystemTime;
+
+// Seconds since the last user input. The sources are tried in order and the first one
+// that answers is used: the X11 screensaver extension, logind then the input devices.
+pub fn idle_time() -> Option<u64> {
+    x11_idle_time()
+        .or_else(logind_idle_time)
+        .or_else(input_idle_time)
+}
+
+fn x11_idle_time() -> Option<u64> {
+    env::var_os("DISPLAY")?;
+
+    let output = Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let millis = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(millis / 1000)
+}
+
+fn logind_idle_time() -> Option<u64> {
+    let user = env::var("USER").ok()?;
+
+    let output = Command::new("loginctl")
+        .args(["show-user", &user, "-p", "IdleHint", "-p", "IdleSinceHint"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut idle_hint = None;
+    let mut idle_since = None;
+    for line in stdout.lines() {
+        match line.split_once('=') {
+            Some(("IdleHint", value)) => idle_hint = Some(value == "yes"),
+            Some(("IdleSinceHint", value)) => idle_since = value.parse::<i64>().ok(),
+            _ => (),
+        }
+    }
+
+    match (idle_hint?, idle_since) {
+        (false, _) => Some(0),
+        // IdleSinceHint is a number of microseconds since the epoch
+        (true, Some(since)) if since > 0 => {
+            let now = Utc::now().timestamp_micros();
+            Some((now - since).max(0) as u64 / 1_000_000)
+        }
+        _ => None,
+    }
+}
+
+fn input_idle_time() -> Option<u64> {
+    let mut last_activity: Option<SystemTime> = None;
+
+    for entry in fs::read_dir("/dev/input").ok()?.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("event") {
+            continue;
+        }
+
+        if let Ok(metadata) = entry.metadata()
+            && let Ok(accessed) = metadata.accessed()
+        {
+            last_activity = Some(last_activity.map_or(accessed, |last| last.max(accessed)));
+        }
+    }
+
+    let elapsed = SystemTime::now().duration_since(last_activity?).ok()?;
+    Some(elapsed.as_secs())
+}
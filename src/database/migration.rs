@@ -0,0 +1,386 @@
+use rusqlite::{Connection, OptionalExtension, Result, ffi};
+
+use super::backend::{SCREENTIME, quote_identifier};
+
+struct Migration {
+    description: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+// The schema version stored in `PRAGMA user_version` is the number of migrations applied.
+// New migrations must only be appended to this list.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "create the time, settings and notification tables",
+        apply: initial_schema,
+    },
+    Migration {
+        description: "move the usage times into the apps and usage tables",
+        apply: normalized_usage,
+    },
+    Migration {
+        description: "store the usage times in seconds instead of minutes",
+        apply: usage_in_seconds,
+    },
+    Migration {
+        description: "add the time spent in the foreground to the usage table",
+        apply: foreground_usage,
+    },
+    Migration {
+        description: "create the matchers table",
+        apply: matchers,
+    },
+    Migration {
+        description: "create the groups, group_members and group_usage tables",
+        apply: groups,
+    },
+    Migration {
+        description: "create the usage_hourly table",
+        apply: usage_hourly,
+    },
+    Migration {
+        description: "create the sessions table",
+        apply: sessions,
+    },
+    Migration {
+        description: "create the usage_monthly and group_usage_monthly tables",
+        apply: monthly_usage,
+    },
+    Migration {
+        description: "create the notification_log table",
+        apply: notification_log,
+    },
+    Migration {
+        description: "add the reminders to the notification and notification_log tables",
+        apply: notification_reminders,
+    },
+    Migration {
+        description: "allow several thresholds with a warning and a message per application",
+        apply: notification_thresholds,
+    },
+    Migration {
+        description: "add the days and the period to the notifications and log them by id",
+        apply: notification_periods,
+    },
+    Migration {
+        description: "add the actions to the notifications and create the snooze table",
+        apply: notification_actions,
+    },
+];
+
+pub const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+pub fn run_migrations(conn: &Connection) -> Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+    if version > SCHEMA_VERSION {
+        return Err(rusqlite::Error::SqliteFailure(
+            ffi::Error::new(ffi::SQLITE_CANTOPEN),
+            Some(format!(
+                "the database uses the schema version {} but this version of the application only supports up to {}",
+                version, SCHEMA_VERSION
+            )),
+        ));
+    }
+
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let is_new = conn
+        .query_row("SELECT 1 FROM sqlite_master LIMIT 1", [], |_| Ok(()))
+        .optional()?
+        .is_none();
+
+    let tx = conn.unchecked_transaction()?;
+    for migration in &MIGRATIONS[version..] {
+        (migration.apply)(&tx)?;
+    }
+    tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    tx.commit()?;
+
+    if !is_new {
+        eprintln!(
+            "Database upgraded from schema version {} to {} :",
+            version, SCHEMA_VERSION
+        );
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            eprintln!("  {} : {}", i + 1, migration.description);
+        }
+    }
+
+    Ok(())
+}
+
+fn initial_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS time (
+            date DATE PRIMARY KEY,
+            pc INTEGER DEFAULT 0
+        );
+        CREATE TABLE IF NOT EXISTS settings (
+            attribute TEXT PRIMARY KEY,
+            value TEXT
+        );
+        CREATE TABLE IF NOT EXISTS notification (
+            app TEXT PRIMARY KEY,
+            time INTEGER
+        );",
+    )
+}
+
+// Databases created before this version stored one column per application in the `time`
+// table. Their content is copied into `apps` and `usage` before the table is dropped.
+fn normalized_usage(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS apps (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            tracked INTEGER NOT NULL DEFAULT 1
+        );
+        CREATE TABLE IF NOT EXISTS usage (
+            app_id INTEGER NOT NULL REFERENCES apps(id),
+            date DATE NOT NULL,
+            minutes INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (app_id, date)
+        );",
+    )?;
+
+    conn.execute(
+        "INSERT OR IGNORE INTO apps (name) VALUES (?1)",
+        (SCREENTIME,),
+    )?;
+
+    let mut names = Vec::new();
+    {
+        let mut stmt = conn.prepare("PRAGMA table_info(time)")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+        for name in rows {
+            let name = name?;
+            if name != "date" {
+                names.push(name);
+            }
+        }
+    }
+
+    for name in &names {
+        conn.execute("INSERT OR IGNORE INTO apps (name) VALUES (?1)", (name,))?;
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO usage (app_id, date, minutes)
+                SELECT (SELECT id FROM apps WHERE name = ?1), date, {0} FROM time
+                WHERE {0} > 0",
+                quote_identifier(name)
+            ),
+            (name,),
+        )?;
+    }
+
+    conn.execute("DROP TABLE time", ())?;
+    Ok(())
+}
+
+fn usage_in_seconds(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE usage RENAME COLUMN minutes TO seconds;
+        UPDATE usage SET seconds = seconds * 60;",
+    )
+}
+
+fn foreground_usage(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "ALTER TABLE usage ADD COLUMN foreground_seconds INTEGER NOT NULL DEFAULT 0",
+        (),
+    )?;
+    Ok(())
+}
+
+fn matchers(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE matchers (
+            app_id INTEGER NOT NULL REFERENCES apps(id),
+            kind TEXT NOT NULL,
+            pattern TEXT NOT NULL,
+            PRIMARY KEY (app_id, kind, pattern)
+        )",
+        (),
+    )?;
+    Ok(())
+}
+
+fn groups(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE groups (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE
+        );
+        CREATE TABLE group_members (
+            group_id INTEGER NOT NULL REFERENCES groups(id),
+            app_id INTEGER NOT NULL REFERENCES apps(id),
+            PRIMARY KEY (group_id, app_id)
+        );
+        CREATE TABLE group_usage (
+            group_id INTEGER NOT NULL REFERENCES groups(id),
+            date DATE NOT NULL,
+            seconds INTEGER NOT NULL DEFAULT 0,
+            foreground_seconds INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (group_id, date)
+        );",
+    )
+}
+
+fn usage_hourly(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE usage_hourly (
+            app_id INTEGER NOT NULL REFERENCES apps(id),
+            date DATE NOT NULL,
+            hour INTEGER NOT NULL,
+            seconds INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (app_id, date, hour)
+        )",
+        (),
+    )?;
+    Ok(())
+}
+
+fn sessions(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE sessions (
+            id INTEGER PRIMARY KEY,
+            app_id INTEGER NOT NULL REFERENCES apps(id),
+            started_at INTEGER NOT NULL,
+            ended_at INTEGER NOT NULL,
+            open INTEGER NOT NULL DEFAULT 1
+        );
+        CREATE INDEX sessions_app_started ON sessions (app_id, started_at);",
+    )
+}
+
+fn monthly_usage(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE usage_monthly (
+            app_id INTEGER NOT NULL REFERENCES apps(id),
+            month TEXT NOT NULL,
+            seconds INTEGER NOT NULL DEFAULT 0,
+            foreground_seconds INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (app_id, month)
+        );
+        CREATE TABLE group_usage_monthly (
+            group_id INTEGER NOT NULL REFERENCES groups(id),
+            month TEXT NOT NULL,
+            seconds INTEGER NOT NULL DEFAULT 0,
+            foreground_seconds INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (group_id, month)
+        );",
+    )
+}
+
+fn notification_log(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE notification_log (
+            app TEXT NOT NULL,
+            date DATE NOT NULL,
+            threshold INTEGER NOT NULL,
+            sent_at INTEGER NOT NULL,
+            PRIMARY KEY (app, date, threshold)
+        )",
+        (),
+    )?;
+    Ok(())
+}
+
+fn notification_reminders(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE notification ADD COLUMN repeat_minutes INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE notification ADD COLUMN critical_after INTEGER;
+        ALTER TABLE notification_log ADD COLUMN reminders INTEGER NOT NULL DEFAULT 0;",
+    )
+}
+
+// The notifications were keyed by application, the tables are rebuilt to key them by
+// application and threshold
+fn notification_thresholds(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE notification_new (
+            app TEXT NOT NULL,
+            time INTEGER NOT NULL,
+            warning_minutes INTEGER NOT NULL DEFAULT 0,
+            repeat_minutes INTEGER NOT NULL DEFAULT 0,
+            critical_after INTEGER,
+            message TEXT,
+            PRIMARY KEY (app, time)
+        );
+        INSERT INTO notification_new (app, time, repeat_minutes, critical_after)
+        SELECT app, time, repeat_minutes, critical_after FROM notification
+        WHERE time IS NOT NULL;
+        DROP TABLE notification;
+        ALTER TABLE notification_new RENAME TO notification;
+        CREATE TABLE notification_log_new (
+            app TEXT NOT NULL,
+            date DATE NOT NULL,
+            threshold INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            sent_at INTEGER NOT NULL,
+            reminders INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (app, date, threshold, kind)
+        );
+        INSERT INTO notification_log_new (app, date, threshold, kind, sent_at, reminders)
+        SELECT app, date, threshold, 'limit', sent_at, reminders FROM notification_log;
+        DROP TABLE notification_log;
+        ALTER TABLE notification_log_new RENAME TO notification_log;",
+    )
+}
+
+// The weekly limits are logged on the first day of their week, the log refers to the
+// notification instead of its application and threshold
+fn notification_periods(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE notification_new (
+            id INTEGER PRIMARY KEY,
+            app TEXT NOT NULL,
+            time INTEGER NOT NULL,
+            period TEXT NOT NULL DEFAULT 'day',
+            days INTEGER NOT NULL DEFAULT 127,
+            warning_minutes INTEGER NOT NULL DEFAULT 0,
+            repeat_minutes INTEGER NOT NULL DEFAULT 0,
+            critical_after INTEGER,
+            message TEXT,
+            UNIQUE (app, period, days, time)
+        );
+        INSERT INTO notification_new
+        (app, time, warning_minutes, repeat_minutes, critical_after, message)
+        SELECT app, time, warning_minutes, repeat_minutes, critical_after, message
+        FROM notification;
+        DROP TABLE notification;
+        ALTER TABLE notification_new RENAME TO notification;
+        CREATE TABLE notification_log_new (
+            notif_id INTEGER NOT NULL REFERENCES notification(id),
+            date DATE NOT NULL,
+            kind TEXT NOT NULL,
+            sent_at INTEGER NOT NULL,
+            reminders INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (notif_id, date, kind)
+        );
+        INSERT INTO notification_log_new (notif_id, date, kind, sent_at, reminders)
+        SELECT n.id, l.date, l.kind, l.sent_at, l.reminders FROM notification_log l
+        JOIN notification n ON n.app = l.app AND n.time = l.threshold;
+        DROP TABLE notification_log;
+        ALTER TABLE notification_log_new RENAME TO notification_log;",
+    )
+}
+
+fn notification_actions(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE notification ADD COLUMN action TEXT;
+        ALTER TABLE notification ADD COLUMN command TEXT;
+        ALTER TABLE notification ADD COLUMN grace_minutes INTEGER NOT NULL DEFAULT 5;
+        CREATE TABLE snooze (
+            id INTEGER PRIMARY KEY,
+            app TEXT NOT NULL,
+            date DATE NOT NULL,
+            reason TEXT NOT NULL,
+            granted_at INTEGER NOT NULL
+        );
+        CREATE INDEX snooze_app_date ON snooze (app, date);",
+    )
+}
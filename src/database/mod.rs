@@ -1,177 +1,805 @@
-use chrono::{Duration, NaiveDate};
-use notify_rust::Notification;
-use rusqlite::{Connection, Result, params};
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveTime, Timelike, Utc};
+use notify_rust::{Notification, Urgency};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::error::{Error, Result};
 
 mod backend;
+mod enforce;
+mod focus;
+mod idle;
+mod migration;
+mod output;
 mod structure;
+#[cfg(test)]
+mod tests;
+mod zone;
 
 use backend::*;
+pub use backend::{Counting, Increments, SCREENTIME, Tracked, update_values};
+use enforce::{current_uid, run_command, terminate};
+use idle::idle_time;
+use migration::run_migrations;
+pub use output::Output;
+use output::{print_json, print_rows};
+use serde::Serialize;
 pub use structure::*;
+pub use zone::Zone;
+use zone::day_of;
 
 const DEFAULT_NUMBER_DAYS_SAVED: u16 = 100;
-
+const DEFAULT_NUMBER_MONTHS_ARCHIVED: u16 = 60;
+const DEFAULT_IDLE_THRESHOLD: u16 = 0;
+const NUMBER_SECONDS_IN_MINUTE: u64 = 60;
+const NUMBER_SECONDS_IN_HOUR: i64 = 3600;
+// The weekly limits read the days of the week, which must not be rolled up yet
+pub const NUMBER_DAYS_IN_WEEK: u16 = 7;
+// Delay tolerated between two updates for a run of an application to go on in the same session
+const SESSION_GAP_SECONDS: i64 = 60;
+
+#[derive(Serialize)]
 struct Settings {
     state: String,
     storage_size: u16,
+    // Months during which the monthly totals are kept, 0 keeps them forever
+    archive_size: u16,
+    // Minutes without input after which the time stops being counted, 0 disables it
+    idle_threshold: u16,
+    idle_apps: String,
+    foreground: String,
+    timezone: Zone,
+    day_start: NaiveTime,
 }
 
 impl std::fmt::Display for Settings {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "\tSettings :\nState : {}\nStorage size : {}",
-            self.state, self.storage_size
+            "\tSettings :\nState : {}\nStorage size : {} days of daily data\nArchive size : {} months of monthly totals\nIdle threshold : {}\nIdle applications : {}\nForeground : {}\nTime zone : {}\nDay start : {}",
+            self.state,
+            self.storage_size,
+            self.archive_size,
+            self.idle_threshold,
+            self.idle_apps,
+            self.foreground,
+            self.timezone,
+            self.day_start.format("%H:%M")
         )
     }
 }
 
+fn parse_date(value: String) -> rusqlite::Result<NaiveDate> {
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+// Columns of the notification aliased as n read by notif_from_row, the other columns of
+// a query follow them
+const NOTIF_COLUMNS: &str = "n.id, n.app, n.time, n.period, n.days, n.warning_minutes,
+    n.repeat_minutes, n.critical_after, n.message, n.action, n.command, n.grace_minutes";
+
+fn invalid_column(index: usize, value: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        index,
+        rusqlite::types::Type::Text,
+        format!("invalid value {}", value).into(),
+    )
+}
+
+fn notif_from_row(row: &rusqlite::Row) -> rusqlite::Result<(i64, Notif)> {
+    let period: String = row.get(3)?;
+    let Some(period) = Period::parse(&period) else {
+        return Err(invalid_column(3, period));
+    };
+    let action = match row.get::<_, Option<String>>(9)? {
+        Some(action) => Some(Action::parse(&action).ok_or_else(|| invalid_column(9, action))?),
+        None => None,
+    };
+    Ok((
+        row.get(0)?,
+        Notif {
+            app: row.get(1)?,
+            minutes: row.get(2)?,
+            period,
+            days: Days::from_bits(row.get(4)?),
+            warning_minutes: row.get(5)?,
+            repeat_minutes: row.get(6)?,
+            critical_after: row.get(7)?,
+            message: row.get(8)?,
+            action,
+            command: row.get(10)?,
+            grace_minutes: row.get(11)?,
+        },
+    ))
+}
+
+// The names are always passed as parameters to the queries, only the names that could
+// not be displayed or typed again are refused
+fn check_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(Error::usage("the name must not be empty"));
+    }
+
+    if name.chars().any(char::is_control) {
+        return Err(Error::usage("the name must not contain control characters"));
+    }
+
+    Ok(())
+}
+
+fn parse_setting(attribute: &str, value: &str) -> Result<u16> {
+    value
+        .parse()
+        .map_err(|_| Error::Config(format!("{} = {}", attribute, value)))
+}
+
+#[derive(Serialize)]
+struct DayData {
+    date: NaiveDate,
+    apps: Vec<TimeApp>,
+    groups: Vec<TimeApp>,
+}
+
+// Row of the applications and the groups of a day, as a table or CSV
+#[derive(Serialize)]
+struct DayRow<'a> {
+    kind: &'a str,
+    #[serde(flatten)]
+    value: &'a TimeApp,
+}
+
+#[derive(Serialize)]
+struct AppData<'a> {
+    name: &'a str,
+    values: &'a [TimeApp],
+    stats: Stat,
+}
+
+#[derive(Serialize)]
+struct SessionData<'a> {
+    name: &'a str,
+    sessions: &'a [Session],
+    launches: usize,
+    longest: u64,
+}
+
+#[derive(Serialize)]
+struct TimelineRow<'a> {
+    date: NaiveDate,
+    name: &'a str,
+    hour: usize,
+    seconds: u64,
+}
+
+#[derive(Serialize)]
+struct AppEntry {
+    app: String,
+}
+
+#[derive(Serialize)]
+struct MatcherEntry {
+    app: String,
+    kind: String,
+    pattern: String,
+}
+
+#[derive(Serialize)]
+struct GroupEntry {
+    group: String,
+    apps: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NotifKind {
+    // The limit is close
+    Warning,
+    // The limit is reached
+    Limit,
+    // The action of the limit will soon be taken
+    Grace,
+    // The action of the limit was taken
+    Enforcement,
+}
+
+impl NotifKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotifKind::Warning => "warning",
+            NotifKind::Limit => "limit",
+            NotifKind::Grace => "grace",
+            NotifKind::Enforcement => "enforcement",
+        }
+    }
+}
+
+// Notification of the application at this index of the update, the reminder 0 is the
+// first notification of the period
+struct DueNotification {
+    index: usize,
+    id: i64,
+    threshold: u16,
+    period: Period,
+    // Time used during the period
+    used: u64,
+    kind: NotifKind,
+    reminder: u16,
+    urgency: Urgency,
+    message: Option<String>,
+}
+
+// Action of an exceeded limit of the application at this index, or its warning
+struct DueEnforcement {
+    index: usize,
+    id: i64,
+    // The warning is sent and the grace period starts
+    grace: bool,
+    notif: Notif,
+}
+
+fn print_list(values: &ListTimeApp, seconds: bool) {
+    if seconds {
+        println!("{values:#}");
+    } else {
+        println!("{values}");
+    }
+}
+
 pub struct Database {
     conn: Connection,
 }
 
 impl Database {
     pub fn new() -> Result<Database> {
-        let conn = Connection::open(get_path_bdd())?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS time (
-                date DATE PRIMARY KEY,
-                pc INTEGER DEFAULT 0
-            )",
-            (),
-        )?;
-
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS settings (
-                attribute TEXT PRIMARY KEY,
-                value TEXT
-            )",
-            (),
-        )?;
+        Database::open(Connection::open(get_path_bdd())?)
+    }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS notification (
-                app TEXT PRIMARY KEY,
-                time INTEGER
-            )",
-            (),
-        )?;
+    #[cfg(test)]
+    fn in_memory() -> Result<Database> {
+        Database::open(Connection::open_in_memory()?)
+    }
 
+    fn open(conn: Connection) -> Result<Database> {
+        run_migrations(&conn)?;
         Ok(Database { conn })
     }
 
     pub fn update(&self) -> Result<()> {
-        if self.get_settings()?.state == "on" {
-            self.delete_old_data()?;
+        if self.is_enabled()? {
+            self.apply_retention()?;
             self.increment_time()?;
         }
 
         Ok(())
     }
 
-    fn increment_time(&self) -> Result<()> {
-        let column_names = self.get_column_name()?;
-        let mut values = self.get_values()?;
+    pub fn is_enabled(&self) -> Result<bool> {
+        Ok(self.get_settings()?.state == "on")
+    }
 
-        if column_names.len() != values.len() {
-            panic!("The number of columns and data are not the same.");
-        }
+    // Once the user has been idle for longer than the threshold, the screen time stops
+    // being counted, and the applications too if the idle_apps setting is on
+    pub fn counting(&self) -> Result<Counting> {
+        let settings = self.get_settings()?;
+        let threshold = settings.idle_threshold as u64 * NUMBER_SECONDS_IN_MINUTE;
+
+        let idle = threshold > 0 && idle_time().is_some_and(|time| time >= threshold);
+        Ok(Counting {
+            pc: !idle,
+            apps: !(idle && settings.idle_apps == "on"),
+            foreground: settings.foreground == "on",
+        })
+    }
 
-        self.conn
-            .execute("DELETE FROM time WHERE date = CURRENT_DATE", ())?;
+    fn increment_time(&self) -> Result<()> {
+        let tracked = self.get_tracked()?;
+        let mut increments = Increments::new(&tracked);
+
+        update_values(
+            &tracked,
+            &mut increments,
+            NUMBER_SECONDS_IN_MINUTE,
+            self.counting()?,
+        )?;
+        self.add_time(&tracked, &increments)
+    }
 
-        update_values(&column_names, &mut values);
-        self.check_notif(&column_names, &values)?;
+    pub fn add_time(&self, tracked: &Tracked, increments: &Increments) -> Result<()> {
+        let date = self.record_time(tracked, increments)?;
+        self.check_limits(tracked, increments, date)
+    }
 
-        let query = format_query(column_names, values);
-        self.conn.execute(&query, [])?;
+    // Adds the increments to the day they belong to and returns it
+    pub fn record_time(&self, tracked: &Tracked, increments: &Increments) -> Result<NaiveDate> {
+        let now = self.zone()?.now();
+        let date = day_of(now, self.day_start()?);
+        let names: Vec<String> = tracked.apps.iter().map(|app| app.name.clone()).collect();
+
+        // The increments are added in the database, so that another process recording
+        // at the same time does not lose its own
+        let tx = self.conn.unchecked_transaction()?;
+        for (name, increment) in names.iter().zip(&increments.apps) {
+            tx.execute(
+                "INSERT INTO usage (app_id, date, seconds, foreground_seconds)
+                SELECT id, ?4, ?2, ?3 FROM apps WHERE name = ?1
+                ON CONFLICT (app_id, date) DO UPDATE SET seconds = seconds + excluded.seconds,
+                foreground_seconds = foreground_seconds + excluded.foreground_seconds",
+                (
+                    name,
+                    increment.running,
+                    increment.foreground,
+                    date.to_string(),
+                ),
+            )?;
+
+            if increment.running > 0 {
+                tx.execute(
+                    "INSERT INTO usage_hourly (app_id, date, hour, seconds)
+                    SELECT id, ?3, ?4, ?2 FROM apps WHERE name = ?1
+                    ON CONFLICT (app_id, date, hour) DO UPDATE SET
+                    seconds = seconds + excluded.seconds",
+                    (name, increment.running, date.to_string(), now.hour()),
+                )?;
+            }
 
-        Ok(())
+            Self::record_session(&tx, name, increment)?;
+        }
+        for (group, increment) in tracked.groups.iter().zip(&increments.groups) {
+            tx.execute(
+                "INSERT INTO group_usage (group_id, date, seconds, foreground_seconds)
+                SELECT id, ?4, ?2, ?3 FROM groups WHERE name = ?1
+                ON CONFLICT (group_id, date) DO UPDATE SET
+                seconds = seconds + excluded.seconds,
+                foreground_seconds = foreground_seconds + excluded.foreground_seconds",
+                (
+                    &group.name,
+                    increment.running,
+                    increment.foreground,
+                    date.to_string(),
+                ),
+            )?;
+        }
+        tx.commit()?;
+        Ok(date)
     }
 
-    fn delete_old_data(&self) -> Result<()> {
-        let mut storage_size = DEFAULT_NUMBER_DAYS_SAVED;
-        if let Some(value) = self.get_attribute("storage_size")? {
-            storage_size = value.parse::<u16>().unwrap_or(DEFAULT_NUMBER_DAYS_SAVED);
+    // The notifications and the actions only follow the time once it is recorded, and one
+    // that fails does not prevent the other ones
+    pub fn check_limits(
+        &self,
+        tracked: &Tracked,
+        increments: &Increments,
+        date: NaiveDate,
+    ) -> Result<()> {
+        let names: Vec<String> = tracked.apps.iter().map(|app| app.name.clone()).collect();
+        let values = self.get_values(&names, date)?;
+        let notified = self.check_notif(&names, &values, date);
+        let running: Vec<bool> = increments.apps.iter().map(|app| app.running > 0).collect();
+        let enforced = self.enforce_limits(tracked, &running, &values, date);
+        notified.and(enforced)
+    }
+
+    // Extends the open session of the application if it was still running at its end,
+    // otherwise closes it and opens a new one. Not running closes the open session.
+    fn record_session(conn: &Connection, name: &str, increment: &Increment) -> Result<()> {
+        if let (Some(first_seen), Some(last_seen)) = (increment.first_seen, increment.last_seen) {
+            let extended = conn.execute(
+                "UPDATE sessions SET ended_at = ?3
+                WHERE open = 1 AND ended_at >= ?2 - ?4
+                AND app_id = (SELECT id FROM apps WHERE name = ?1)",
+                (name, first_seen, last_seen, SESSION_GAP_SECONDS),
+            )?;
+            if extended > 0 {
+                return Ok(());
+            }
         }
 
-        self.conn.execute(
-            "DELETE FROM time WHERE JULIANDAY(DATE()) - JULIANDAY(date) > ?1",
-            ((&storage_size),),
+        conn.execute(
+            "UPDATE sessions SET open = 0
+            WHERE open = 1 AND app_id = (SELECT id FROM apps WHERE name = ?1)",
+            (name,),
         )?;
 
+        if let (Some(first_seen), Some(last_seen)) = (increment.first_seen, increment.last_seen) {
+            conn.execute(
+                "INSERT INTO sessions (app_id, started_at, ended_at)
+                SELECT id, ?2, ?3 FROM apps WHERE name = ?1",
+                (name, first_seen, last_seen),
+            )?;
+        }
         Ok(())
     }
 
-    fn get_column_name(&self) -> Result<Vec<String>> {
-        let mut stmt = self.conn.prepare("PRAGMA table_info(time)")?;
+    // The days older than the storage size are rolled up into the totals of their month,
+    // which are kept for the archive size. Their hours and sessions are not kept.
+    pub fn apply_retention(&self) -> Result<()> {
+        let settings = self.get_settings()?;
+        let oldest = self.daily_horizon()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO usage_monthly (app_id, month, seconds, foreground_seconds)
+            SELECT app_id, strftime('%Y-%m', date), SUM(seconds), SUM(foreground_seconds)
+            FROM usage WHERE date < ?1 GROUP BY app_id, strftime('%Y-%m', date)
+            ON CONFLICT (app_id, month) DO UPDATE SET
+            seconds = seconds + excluded.seconds,
+            foreground_seconds = foreground_seconds + excluded.foreground_seconds",
+            (oldest.to_string(),),
+        )?;
+        tx.execute(
+            "INSERT INTO group_usage_monthly (group_id, month, seconds, foreground_seconds)
+            SELECT group_id, strftime('%Y-%m', date), SUM(seconds), SUM(foreground_seconds)
+            FROM group_usage WHERE date < ?1 GROUP BY group_id, strftime('%Y-%m', date)
+            ON CONFLICT (group_id, month) DO UPDATE SET
+            seconds = seconds + excluded.seconds,
+            foreground_seconds = foreground_seconds + excluded.foreground_seconds",
+            (oldest.to_string(),),
+        )?;
+        tx.execute("DELETE FROM usage WHERE date < ?1", (oldest.to_string(),))?;
+        tx.execute(
+            "DELETE FROM group_usage WHERE date < ?1",
+            (oldest.to_string(),),
+        )?;
+        tx.execute(
+            "DELETE FROM usage_hourly WHERE date < ?1",
+            (oldest.to_string(),),
+        )?;
+        tx.execute("DELETE FROM snooze WHERE date < ?1", (oldest.to_string(),))?;
+        // The weekly limits are logged on the first day of their week
+        tx.execute(
+            "DELETE FROM notification_log WHERE date < ?1",
+            (Period::Week.start(oldest).to_string(),),
+        )?;
 
-        let column_names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+        if let Some(timestamp) = settings
+            .timezone
+            .timestamp(oldest.and_time(settings.day_start))
+        {
+            tx.execute("DELETE FROM sessions WHERE started_at < ?1", (timestamp,))?;
+        }
 
-        let mut names = Vec::new();
-        for name in column_names {
-            names.push(name?);
+        if settings.archive_size > 0
+            && let Some(month) =
+                oldest.checked_sub_months(Months::new(settings.archive_size as u32))
+        {
+            let month = month.format("%Y-%m").to_string();
+            tx.execute("DELETE FROM usage_monthly WHERE month < ?1", (&month,))?;
+            tx.execute(
+                "DELETE FROM group_usage_monthly WHERE month < ?1",
+                (&month,),
+            )?;
         }
-        names.remove(0);
-        Ok(names)
+        tx.commit()?;
+        Ok(())
     }
 
-    fn get_values(&self) -> Result<Vec<u16>> {
+    // Oldest day whose data is still kept day by day
+    fn daily_horizon(&self) -> Result<NaiveDate> {
+        let storage_size = self.get_settings()?.storage_size;
+        Ok(self.today()? - Duration::days(storage_size as i64))
+    }
+
+    pub fn get_tracked(&self) -> Result<Tracked> {
         let mut stmt = self
             .conn
-            .prepare("SELECT * FROM time WHERE date = CURRENT_DATE")?;
+            .prepare("SELECT id, name FROM apps WHERE tracked = 1 ORDER BY id")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?;
+
+        let mut ids = Vec::new();
+        let mut apps = Vec::new();
+        for row in rows {
+            let (id, name) = row?;
+            ids.push(id);
+            apps.push(TrackedApp {
+                name,
+                matchers: self.get_matchers(id)?,
+            });
+        }
 
-        let column_count = stmt.column_count();
+        let mut stmt = self.conn.prepare(
+            "SELECT g.name, m.app_id FROM groups g
+            LEFT JOIN group_members m ON m.group_id = g.id ORDER BY g.id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+        })?;
 
-        let mut rows = stmt.query_map([], |row| {
-            let mut values: Vec<u16> = Vec::new();
+        let mut groups: Vec<TrackedGroup> = Vec::new();
+        for row in rows {
+            let (name, app_id) = row?;
+            if groups.last().is_none_or(|g| g.name != name) {
+                groups.push(TrackedGroup {
+                    name,
+                    members: Vec::new(),
+                });
+            }
 
-            for i in 1..column_count {
-                values.push(row.get(i)?);
+            // Members that are no longer tracked are ignored
+            if let Some(index) = app_id.and_then(|id| ids.iter().position(|i| *i == id))
+                && let Some(group) = groups.last_mut()
+            {
+                group.members.push(index);
             }
+        }
 
-            Ok(values)
+        Ok(Tracked { apps, groups })
+    }
+
+    fn get_matchers(&self, app_id: i64) -> Result<Vec<Matcher>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT kind, pattern FROM matchers WHERE app_id = ?1")?;
+        let rows = stmt.query_map([app_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
         })?;
 
-        if let Some(Ok(values)) = rows.next() {
-            return Ok(values);
+        let mut matchers = Vec::new();
+        for row in rows {
+            let (kind, pattern) = row?;
+            match Matcher::new(&kind, &pattern) {
+                Ok(matcher) => matchers.push(matcher),
+                Err(e) => eprintln!("Error : ignored matcher {}:{} : {}", kind, pattern, e),
+            }
         }
 
-        Ok(vec![0; column_count - 1])
+        Ok(matchers)
     }
 
-    pub fn add_app(&self, name: String) -> Result<()> {
-        if name != "date" && !self.contain_names(&name)? {
-            let query = format!("ALTER TABLE time ADD COLUMN [{}] INTEGER DEFAULT 0", &name);
-            self.conn.execute(&query, [])?;
+    fn get_values(&self, names: &[String], date: NaiveDate) -> Result<Vec<u64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT u.seconds FROM usage u JOIN apps a ON a.id = u.app_id
+            WHERE a.name = ?1 AND u.date = ?2",
+        )?;
 
-            return Ok(());
+        let mut values = Vec::new();
+        for name in names {
+            let value = stmt
+                .query_row((name, date.to_string()), |row| row.get::<_, u64>(0))
+                .optional()?;
+            values.push(value.unwrap_or(0));
+        }
+
+        Ok(values)
+    }
+
+    fn get_app_id(&self, name: &str) -> Result<Option<(i64, bool)>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT id, tracked FROM apps WHERE name = ?1",
+                [name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?)
+    }
+
+    pub fn add_app(&self, name: String) -> Result<()> {
+        match self.get_app_id(&name)? {
+            Some((_, true)) => {
+                return Err(Error::usage("The app you want to add is already present"));
+            }
+            Some((id, false)) => {
+                self.conn
+                    .execute("UPDATE apps SET tracked = 1 WHERE id = ?1", (id,))?;
+            }
+            None => Self::create_app(&self.conn, &name, true)?,
         }
 
-        eprintln!("Error : The app you want to add is already present");
+        Ok(())
+    }
+
+    fn create_app(conn: &Connection, name: &str, tracked: bool) -> Result<()> {
+        check_name(name)?;
+        conn.execute(
+            "INSERT INTO apps (name, tracked) VALUES (?1, ?2)",
+            (name, tracked),
+        )?;
         Ok(())
     }
 
     pub fn del_app(&self, name: String) -> Result<()> {
-        if name == "date" || name == SCREENTIME {
-            eprintln!("Error : You cannot delete the {} column", name);
-            return Ok(());
+        if name == SCREENTIME {
+            return Err(Error::usage(format!(
+                "You cannot delete the {} application",
+                name
+            )));
         }
 
+        // The history of the application is kept so that it can still be queried
         if self.contain_names(&name)? {
-            let query = format!("ALTER TABLE time DROP [{}]", &name);
-            self.conn.execute(&query, [])?;
-            self.del_notif(&name)?;
+            self.conn
+                .execute("UPDATE apps SET tracked = 0 WHERE name = ?1", (&name,))?;
+            self.del_notif(&name, None)?;
             return Ok(());
         }
 
-        eprintln!("Error : The application you want to delete does not exist");
+        Err(Error::usage(
+            "The application you want to delete does not exist",
+        ))
+    }
+
+    pub fn contain_names(&self, name: &str) -> Result<bool> {
+        Ok(matches!(self.get_app_id(name)?, Some((_, true))))
+    }
+
+    fn get_group_id(&self, name: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row("SELECT id FROM groups WHERE name = ?1", [name], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    pub fn contain_group(&self, name: &str) -> Result<bool> {
+        Ok(self.get_group_id(name)?.is_some())
+    }
+
+    // The group is created if it does not exist yet
+    pub fn add_group(&self, name: &str, apps: &[&str]) -> Result<()> {
+        check_name(name)?;
+        for app in apps {
+            if !self.contain_names(app)? {
+                return Err(Error::usage(format!(
+                    "The application {} is not followed",
+                    app
+                )));
+            }
+        }
+
+        self.conn
+            .execute("INSERT OR IGNORE INTO groups (name) VALUES (?1)", (name,))?;
+        for app in apps {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO group_members (group_id, app_id)
+                SELECT g.id, a.id FROM groups g, apps a WHERE g.name = ?1 AND a.name = ?2",
+                (name, app),
+            )?;
+        }
+
         Ok(())
     }
 
-    pub fn contain_names(&self, name: &String) -> Result<bool> {
-        let column_names = self.get_column_name()?;
-        Ok(column_names.contains(name))
+    // Without applications, the group and its history are deleted
+    pub fn del_group(&self, name: &str, apps: &[&str]) -> Result<()> {
+        let Some(id) = self.get_group_id(name)? else {
+            return Err(Error::usage("The group you want to delete does not exist"));
+        };
+
+        if !apps.is_empty() {
+            for app in apps {
+                self.conn.execute(
+                    "DELETE FROM group_members WHERE group_id = ?1
+                    AND app_id = (SELECT id FROM apps WHERE name = ?2)",
+                    (id, app),
+                )?;
+            }
+            return Ok(());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM group_members WHERE group_id = ?1", (id,))?;
+        tx.execute("DELETE FROM group_usage WHERE group_id = ?1", (id,))?;
+        tx.execute("DELETE FROM group_usage_monthly WHERE group_id = ?1", (id,))?;
+        tx.execute("DELETE FROM groups WHERE id = ?1", (id,))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn print_groups(&self, output: Output) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT g.name, COALESCE(GROUP_CONCAT(a.name, ', '), '') FROM groups g
+            LEFT JOIN group_members m ON m.group_id = g.id
+            LEFT JOIN apps a ON a.id = m.app_id
+            GROUP BY g.id ORDER BY g.id",
+        )?;
+        let rows = stmt.query_map(params![], |row| {
+            Ok(GroupEntry {
+                group: row.get(0)?,
+                apps: row.get(1)?,
+            })
+        })?;
+        let entries: Vec<GroupEntry> = rows.flatten().collect();
+
+        match output {
+            Output::Plain => {
+                println!("\tList of groups :");
+                for entry in &entries {
+                    println!("{} => {}", entry.group, entry.apps);
+                }
+                println!();
+            }
+            Output::Json => print_json(&entries)?,
+            Output::Table | Output::Csv => print_rows(output, &entries)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn add_matcher(&self, name: &str, kind: &str, pattern: &str) -> Result<()> {
+        let Some((id, true)) = self.get_app_id(name)? else {
+            return Err(Error::usage("This application is not followed"));
+        };
+
+        if let Err(e) = Matcher::new(kind, pattern) {
+            return Err(Error::usage(format!("invalid matcher : {}", e)));
+        }
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO matchers (app_id, kind, pattern) VALUES (?1, ?2, ?3)",
+            (id, kind, pattern),
+        )?;
+        Ok(())
+    }
+
+    // Without a kind and a pattern, all the matchers of the application are removed
+    pub fn del_matcher(&self, name: &str, matcher: Option<(&str, &str)>) -> Result<()> {
+        let Some((id, _)) = self.get_app_id(name)? else {
+            return Err(Error::usage("This application is not followed"));
+        };
+
+        match matcher {
+            Some((kind, pattern)) => self.conn.execute(
+                "DELETE FROM matchers WHERE app_id = ?1 AND kind = ?2 AND pattern = ?3",
+                (id, kind, pattern),
+            )?,
+            None => self
+                .conn
+                .execute("DELETE FROM matchers WHERE app_id = ?1", (id,))?,
+        };
+        Ok(())
+    }
+
+    pub fn print_matchers(&self, output: Output) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name, m.kind, m.pattern FROM matchers m JOIN apps a ON a.id = m.app_id
+            WHERE a.tracked = 1 ORDER BY a.id, m.kind, m.pattern",
+        )?;
+        let rows = stmt.query_map(params![], |row| {
+            Ok(MatcherEntry {
+                app: row.get(0)?,
+                kind: row.get(1)?,
+                pattern: row.get(2)?,
+            })
+        })?;
+        let entries: Vec<MatcherEntry> = rows.flatten().collect();
+
+        match output {
+            Output::Plain => {
+                println!("\tList of matchers :");
+                for entry in &entries {
+                    println!("{} => {}:{}", entry.app, entry.kind, entry.pattern);
+                }
+                println!();
+            }
+            Output::Json => print_json(&entries)?,
+            Output::Table | Output::Csv => print_rows(output, &entries)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn print_apps(&self, output: Output) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM apps WHERE tracked = 1 ORDER BY id")?;
+        let rows = stmt.query_map(params![], |row| Ok(AppEntry { app: row.get(0)? }))?;
+        let entries: Vec<AppEntry> = rows.flatten().collect();
+
+        match output {
+            Output::Plain => {
+                println!("\tList of applications :");
+                for entry in &entries {
+                    println!("{}", entry.app);
+                }
+                println!();
+            }
+            Output::Json => print_json(&entries)?,
+            Output::Table | Output::Csv => print_rows(output, &entries)?,
+        }
+
+        Ok(())
     }
 
     fn get_settings(&self) -> Result<Settings> {
@@ -182,17 +810,75 @@ impl Database {
 
         let mut storage_size = DEFAULT_NUMBER_DAYS_SAVED;
         if let Some(value) = self.get_attribute("storage_size")? {
-            storage_size = value.parse::<u16>().unwrap_or(DEFAULT_NUMBER_DAYS_SAVED);
+            storage_size = parse_setting("storage_size", &value)?;
+        }
+
+        let mut archive_size = DEFAULT_NUMBER_MONTHS_ARCHIVED;
+        if let Some(value) = self.get_attribute("archive_size")? {
+            archive_size = parse_setting("archive_size", &value)?;
+        }
+
+        let mut idle_threshold = DEFAULT_IDLE_THRESHOLD;
+        if let Some(value) = self.get_attribute("idle_threshold")? {
+            idle_threshold = parse_setting("idle_threshold", &value)?;
+        }
+
+        let mut idle_apps = String::from("off");
+        if let Some(value) = self.get_attribute("idle_apps")? {
+            idle_apps = value;
         }
 
+        let mut foreground = String::from("off");
+        if let Some(value) = self.get_attribute("foreground")? {
+            foreground = value;
+        }
+
+        let timezone = self.zone()?;
+        let day_start = self.day_start()?;
+
         Ok(Settings {
             state,
             storage_size,
+            archive_size,
+            idle_threshold,
+            idle_apps,
+            foreground,
+            timezone,
+            day_start,
         })
     }
 
-    pub fn display_settings(&self) -> Result<()> {
-        println!("{}", self.get_settings()?);
+    pub fn zone(&self) -> Result<Zone> {
+        let mut zone = Zone::Local;
+        if let Some(value) = self.get_attribute("timezone")? {
+            zone = Zone::parse(&value)
+                .ok_or_else(|| Error::Config(format!("timezone = {}", value)))?;
+        }
+        Ok(zone)
+    }
+
+    // Hour at which the days start, the usage before it belongs to the previous day
+    fn day_start(&self) -> Result<NaiveTime> {
+        let mut day_start = NaiveTime::MIN;
+        if let Some(value) = self.get_attribute("day_start")? {
+            day_start = NaiveTime::parse_from_str(&value, "%H:%M")
+                .map_err(|_| Error::Config(format!("day_start = {}", value)))?;
+        }
+        Ok(day_start)
+    }
+
+    // Date of the current day in the time zone of the settings
+    pub fn today(&self) -> Result<NaiveDate> {
+        Ok(day_of(self.zone()?.now(), self.day_start()?))
+    }
+
+    pub fn display_settings(&self, output: Output) -> Result<()> {
+        let settings = self.get_settings()?;
+        match output {
+            Output::Plain => println!("{}", settings),
+            Output::Json => print_json(&settings)?,
+            Output::Table | Output::Csv => print_rows(output, &[settings])?,
+        }
         Ok(())
     }
 
@@ -230,87 +916,316 @@ impl Database {
     }
 
     pub fn get_time_day(&self, date: NaiveDate) -> Result<Vec<TimeApp>> {
-        let column_names = self.get_column_name()?;
+        if date < self.daily_horizon()? {
+            return self.get_time_month(date);
+        }
 
-        let mut stmt = self.conn.prepare("SELECT * FROM time WHERE date = ?1")?;
-        let mut rows = stmt.query_map(params![date.to_string()], |row| {
-            let mut values: Vec<TimeApp> = Vec::new();
-            for (i, name) in column_names.iter().enumerate() {
-                values.push(TimeApp::new(name.clone(), date, row.get::<_, u16>(i + 1)?))
-            }
-            Ok(values)
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name, COALESCE(u.seconds, 0), COALESCE(u.foreground_seconds, 0) FROM apps a
+            LEFT JOIN usage u ON u.app_id = a.id AND u.date = ?1
+            WHERE a.tracked = 1 OR u.seconds > 0
+            ORDER BY a.id",
+        )?;
+        let rows = stmt.query_map(params![date.to_string()], |row| {
+            Ok(TimeApp::new(
+                row.get(0)?,
+                date,
+                row.get::<_, u64>(1)?,
+                row.get::<_, u64>(2)?,
+            ))
         })?;
 
-        let mut values: Vec<TimeApp> = Vec::new();
-        match rows.next() {
-            Some(Ok(vec_times)) => {
-                for t in vec_times {
-                    values.push(t);
-                }
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn get_time_day_groups(&self, date: NaiveDate) -> Result<Vec<TimeApp>> {
+        if date < self.daily_horizon()? {
+            return self.get_time_month_groups(date);
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT g.name, COALESCE(u.seconds, 0), COALESCE(u.foreground_seconds, 0) FROM groups g
+            LEFT JOIN group_usage u ON u.group_id = g.id AND u.date = ?1
+            ORDER BY g.id",
+        )?;
+        let rows = stmt.query_map(params![date.to_string()], |row| {
+            Ok(TimeApp::new(
+                row.get(0)?,
+                date,
+                row.get::<_, u64>(1)?,
+                row.get::<_, u64>(2)?,
+            ))
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // Totals of the days of the month rolled up or about to be, the more recent days of the
+    // month are kept day by day
+    fn get_time_month(&self, date: NaiveDate) -> Result<Vec<TimeApp>> {
+        let month = date.format("%Y-%m").to_string();
+        let horizon = self.daily_horizon()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name, COALESCE(SUM(t.seconds), 0), COALESCE(SUM(t.foreground_seconds), 0)
+            FROM apps a LEFT JOIN (
+                SELECT app_id, seconds, foreground_seconds FROM usage_monthly WHERE month = ?1
+                UNION ALL
+                SELECT app_id, seconds, foreground_seconds FROM usage
+                WHERE strftime('%Y-%m', date) = ?1 AND date < ?2
+            ) t ON t.app_id = a.id
+            GROUP BY a.id HAVING a.tracked = 1 OR SUM(t.seconds) > 0
+            ORDER BY a.id",
+        )?;
+        let rows = stmt.query_map(params![month, horizon.to_string()], |row| {
+            Ok(TimeApp::new_month(
+                row.get(0)?,
+                date,
+                row.get::<_, u64>(1)?,
+                row.get::<_, u64>(2)?,
+            ))
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    fn get_time_month_groups(&self, date: NaiveDate) -> Result<Vec<TimeApp>> {
+        let month = date.format("%Y-%m").to_string();
+        let horizon = self.daily_horizon()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT g.name, COALESCE(SUM(t.seconds), 0), COALESCE(SUM(t.foreground_seconds), 0)
+            FROM groups g LEFT JOIN (
+                SELECT group_id, seconds, foreground_seconds FROM group_usage_monthly
+                WHERE month = ?1
+                UNION ALL
+                SELECT group_id, seconds, foreground_seconds FROM group_usage
+                WHERE strftime('%Y-%m', date) = ?1 AND date < ?2
+            ) t ON t.group_id = g.id
+            GROUP BY g.id ORDER BY g.id",
+        )?;
+        let rows = stmt.query_map(params![month, horizon.to_string()], |row| {
+            Ok(TimeApp::new_month(
+                row.get(0)?,
+                date,
+                row.get::<_, u64>(1)?,
+                row.get::<_, u64>(2)?,
+            ))
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // Applications without any time on this date are left out
+    pub fn get_timeline(&self, date: NaiveDate) -> Result<Timeline> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.name, h.hour, h.seconds FROM usage_hourly h JOIN apps a ON a.id = h.app_id
+            WHERE h.date = ?1 ORDER BY a.id, h.hour",
+        )?;
+        let rows = stmt.query_map(params![date.to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, usize>(1)?,
+                row.get::<_, u64>(2)?,
+            ))
+        })?;
+
+        let mut values: Vec<TimelineApp> = Vec::new();
+        for row in rows {
+            let (name, hour, seconds) = row?;
+            if values.last().is_none_or(|v| v.name != name) {
+                values.push(TimelineApp {
+                    name,
+                    hours: [0; NUMBER_HOURS_IN_DAY],
+                });
             }
-            _ => {
-                for name in &column_names {
-                    values.push(TimeApp::new(name.clone(), date, 0));
-                }
+
+            if let Some(value) = values.last_mut()
+                && hour < NUMBER_HOURS_IN_DAY
+            {
+                value.hours[hour] = seconds;
             }
         }
 
-        Ok(values)
+        Ok(Timeline {
+            date,
+            first_hour: self.day_start()?.hour() as usize,
+            values,
+        })
     }
 
+    pub fn print_timeline(
+        &self,
+        date: NaiveDate,
+        number_days: u16,
+        reverse: bool,
+        output: Output,
+    ) -> Result<()> {
+        let mut days: Vec<u16> = (0..number_days).collect();
+        if reverse {
+            days.reverse();
+        }
+
+        let mut timelines = Vec::new();
+        for i in days {
+            let date_query = date - chrono::Duration::days(i as i64);
+            timelines.push(self.get_timeline(date_query)?);
+        }
+
+        match output {
+            Output::Plain => timelines
+                .iter()
+                .for_each(|timeline| println!("{}", timeline)),
+            Output::Json => print_json(&timelines)?,
+            Output::Table | Output::Csv => {
+                let mut rows = Vec::new();
+                for timeline in &timelines {
+                    for value in &timeline.values {
+                        rows.extend(timeline.hours().map(|hour| TimelineRow {
+                            date: timeline.date,
+                            name: &value.name,
+                            hour,
+                            seconds: value.hours[hour],
+                        }));
+                    }
+                }
+                print_rows(output, &rows)?;
+            }
+        }
+        Ok(())
+    }
+
+    // A group can be observed like an application, an application of the same name
+    // takes precedence
     pub fn get_time_app(
         &self,
         name: &str,
         date: NaiveDate,
         number_days: u16,
     ) -> Result<Vec<TimeApp>> {
-        let query = format!(
-            "SELECT date, [{}] FROM time WHERE date <= '{}' and date > DATE('{}', '-{} days') ORDER BY date DESC",
-            name, date, date, number_days
-        );
-        let mut stmt = self.conn.prepare(&query)?;
-        let rows = stmt.query_map([], |row| {
-            let date = NaiveDate::parse_from_str(&row.get::<_, String>(0)?, "%Y-%m-%d")
-                .expect("Unable to retrieve a date");
-            Ok(TimeApp::new(
-                SCREENTIME.to_string(),
-                date,
-                row.get::<_, u16>(1)?,
-            ))
-        })?;
-
-        let mut values: Vec<TimeApp> = Vec::new();
-        for row in rows.flatten() {
-            values.push(row);
-        }
+        let is_group = self.get_app_id(name)?.is_none() && self.get_group_id(name)?.is_some();
+        let query = if is_group {
+            "SELECT u.date, u.seconds, u.foreground_seconds FROM group_usage u
+            JOIN groups g ON g.id = u.group_id
+            WHERE g.name = ?1 AND u.date <= ?2 AND u.date > DATE(?2, ?3)
+            ORDER BY u.date DESC"
+        } else {
+            "SELECT u.date, u.seconds, u.foreground_seconds FROM usage u JOIN apps a ON a.id = u.app_id
+            WHERE a.name = ?1 AND u.date <= ?2 AND u.date > DATE(?2, ?3)
+            ORDER BY u.date DESC"
+        };
+        let mut stmt = self.conn.prepare(query)?;
+        let mut values = stmt
+            .query_map(
+                params![name, date.to_string(), format!("-{} days", number_days)],
+                |row| {
+                    Ok(TimeApp::new(
+                        name.to_string(),
+                        parse_date(row.get(0)?)?,
+                        row.get::<_, u64>(1)?,
+                        row.get::<_, u64>(2)?,
+                    ))
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
 
         for i in 0..number_days {
             let deadline = date - Duration::days(i as i64);
             if i == values.len() as u16 || values[i as usize].date != deadline {
-                values.insert(
-                    i as usize,
-                    TimeApp::new(SCREENTIME.to_string(), deadline, 0),
-                );
+                values.insert(i as usize, TimeApp::new(name.to_string(), deadline, 0, 0));
             }
         }
 
-        Ok(values)
+        // The days rolled up are replaced by the total of their month
+        let horizon = self.daily_horizon()?;
+        let mut result: Vec<TimeApp> = Vec::new();
+        for value in values {
+            if value.date >= horizon {
+                result.push(value);
+                continue;
+            }
+
+            if result.last().is_some_and(|v| {
+                v.month
+                    && (v.date.year(), v.date.month()) == (value.date.year(), value.date.month())
+            }) {
+                continue;
+            }
+
+            let months = if is_group {
+                self.get_time_month_groups(value.date)?
+            } else {
+                self.get_time_month(value.date)?
+            };
+            let (secs, foreground_secs) = months
+                .iter()
+                .find(|v| v.name == name)
+                .map_or((0, 0), |v| (v.sec_total, v.foreground_total));
+            result.push(TimeApp::new_month(
+                name.to_string(),
+                value.date,
+                secs,
+                foreground_secs,
+            ));
+        }
+
+        Ok(result)
     }
 
-    pub fn print_day_data(&self, date: NaiveDate, number_days: u16, reverse: bool) -> Result<()> {
-        if !reverse {
-            for i in 0..number_days {
-                let date_query = date - chrono::Duration::days(i as i64);
-                let values =
-                    ListTimeApp::new(Type::Day, self.get_time_day(date_query)?, date_query);
-                println!("{values}");
+    pub fn print_day_data(
+        &self,
+        date: NaiveDate,
+        number_days: u16,
+        reverse: bool,
+        seconds: bool,
+        output: Output,
+    ) -> Result<()> {
+        let mut days: Vec<u16> = (0..number_days).collect();
+        if reverse {
+            days.reverse();
+        }
+
+        let mut data = Vec::new();
+        let mut last_month = None;
+        for i in days {
+            let date_query = date - chrono::Duration::days(i as i64);
+            let apps = self.get_time_day(date_query)?;
+
+            // The days rolled up into the same month are displayed once
+            let month = date_query.format("%Y-%m").to_string();
+            if apps.iter().any(|v| v.month) && last_month.as_ref() == Some(&month) {
+                continue;
             }
-        } else {
-            for i in (0..number_days).rev() {
-                let date_query = date - chrono::Duration::days(i as i64);
-                let values =
-                    ListTimeApp::new(Type::Day, self.get_time_day(date_query)?, date_query);
-                println!("{values}");
+            last_month = Some(month);
+
+            data.push(DayData {
+                date: date_query,
+                apps,
+                groups: self.get_time_day_groups(date_query)?,
+            });
+        }
+
+        match output {
+            Output::Plain => {
+                for day in data {
+                    print_list(&ListTimeApp::new(Type::Day, day.apps, day.date), seconds);
+                    if !day.groups.is_empty() {
+                        print_list(
+                            &ListTimeApp::new(Type::Group, day.groups, day.date),
+                            seconds,
+                        );
+                    }
+                }
+            }
+            Output::Json => print_json(&data)?,
+            Output::Table | Output::Csv => {
+                let mut rows = Vec::new();
+                for day in &data {
+                    rows.extend(day.apps.iter().map(|value| DayRow { kind: "app", value }));
+                    rows.extend(day.groups.iter().map(|value| DayRow {
+                        kind: "group",
+                        value,
+                    }));
+                }
+                print_rows(output, &rows)?;
             }
         }
         Ok(())
@@ -322,10 +1237,11 @@ impl Database {
         date: NaiveDate,
         number_days: u16,
         reverse: bool,
+        seconds: bool,
+        output: Output,
     ) -> Result<()> {
-        if !self.contain_names(&name)? {
-            eprintln!("Error : This application is not followed");
-            return Ok(());
+        if self.get_app_id(&name)?.is_none() && self.get_group_id(&name)?.is_none() {
+            return Err(Error::usage("This application is not followed"));
         }
 
         let mut values = self.get_time_app(&name, date, number_days)?;
@@ -333,68 +1249,874 @@ impl Database {
             values.reverse();
         }
 
-        let values = ListTimeApp::new(Type::App(name.clone()), values, date);
-        println!("{values}");
+        match output {
+            Output::Plain => {
+                let values = ListTimeApp::new(Type::App(name.clone()), values, date);
+                print_list(&values, seconds);
+            }
+            Output::Json => print_json(&AppData {
+                name: &name,
+                stats: Stat::new(&values),
+                values: &values,
+            })?,
+            Output::Table | Output::Csv => print_rows(output, &values)?,
+        }
         Ok(())
     }
 
-    pub fn add_notif(&self, name: &String, time: u16) -> Result<()> {
-        if !self.contain_names(name)? {
-            eprintln!("Error : This application is not followed");
-            return Ok(());
+    pub fn get_sessions(
+        &self,
+        name: &str,
+        date: NaiveDate,
+        number_days: u16,
+    ) -> Result<Vec<Session>> {
+        let first_day = date - Duration::days(number_days as i64 - 1);
+        let Some((from, to)) = self.day_bounds(Some(first_day), date)? else {
+            return Ok(Vec::new());
+        };
+        let zone = self.zone()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT s.started_at, s.ended_at FROM sessions s JOIN apps a ON a.id = s.app_id
+            WHERE a.name = ?1 AND s.started_at >= ?2 AND s.started_at < ?3
+            ORDER BY s.started_at DESC",
+        )?;
+        let rows = stmt.query_map((name, from, to), |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut values = Vec::new();
+        for (started_at, ended_at) in rows.flatten() {
+            if let (Some(start), Some(end)) = (zone.datetime(started_at), zone.datetime(ended_at)) {
+                values.push(Session::new(start, end));
+            }
+        }
+
+        Ok(values)
+    }
+
+    pub fn print_sessions(
+        &self,
+        name: String,
+        date: NaiveDate,
+        number_days: u16,
+        reverse: bool,
+        seconds: bool,
+        output: Output,
+    ) -> Result<()> {
+        if self.get_app_id(&name)?.is_none() {
+            return Err(Error::usage("This application is not followed"));
+        }
+
+        let mut values = self.get_sessions(&name, date, number_days)?;
+        if reverse {
+            values.reverse();
+        }
+
+        match output {
+            Output::Plain => {
+                let values = ListSession::new(name, values, number_days);
+                if seconds {
+                    println!("{values:#}");
+                } else {
+                    println!("{values}");
+                }
+            }
+            Output::Json => print_json(&SessionData {
+                name: &name,
+                sessions: &values,
+                launches: values.len(),
+                longest: values.iter().map(|v| v.sec_total).max().unwrap_or(0),
+            })?,
+            Output::Table | Output::Csv => print_rows(output, &values)?,
+        }
+        Ok(())
+    }
+
+    // Timestamps of the start of the first day and of the end of the last one, in the time
+    // zone and with the start of the days of the settings. Without first day, every day
+    // until the last one.
+    fn day_bounds(
+        &self,
+        first_day: Option<NaiveDate>,
+        last_day: NaiveDate,
+    ) -> Result<Option<(i64, i64)>> {
+        let zone = self.zone()?;
+        let day_start = self.day_start()?;
+
+        let from = match first_day {
+            Some(day) => zone.timestamp(day.and_time(day_start)),
+            None => Some(i64::MIN),
+        };
+        let to = zone.timestamp((last_day + Duration::days(1)).and_time(day_start));
+        Ok(from.zip(to))
+    }
+
+    // Usage of the days from first_day to last_day, or of every day until last_day
+    pub fn get_records(
+        &self,
+        first_day: Option<NaiveDate>,
+        last_day: NaiveDate,
+    ) -> Result<Records> {
+        let first = first_day.map(|day| day.to_string());
+        let last = last_day.to_string();
+
+        let mut stmt = self.conn.prepare(
+            "SELECT u.date, a.name, u.seconds, u.foreground_seconds FROM usage u
+            JOIN apps a ON a.id = u.app_id
+            WHERE (?1 IS NULL OR u.date >= ?1) AND u.date <= ?2
+            ORDER BY u.date, a.id",
+        )?;
+        let usage = stmt
+            .query_map((&first, &last), |row| {
+                let seconds = row.get::<_, u64>(2)?;
+                Ok(UsageRecord {
+                    date: parse_date(row.get(0)?)?,
+                    app: row.get(1)?,
+                    minutes: seconds / NUMBER_SECONDS_IN_MINUTE,
+                    seconds,
+                    foreground_seconds: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT h.date, a.name, h.hour, h.seconds FROM usage_hourly h
+            JOIN apps a ON a.id = h.app_id
+            WHERE (?1 IS NULL OR h.date >= ?1) AND h.date <= ?2
+            ORDER BY h.date, a.id, h.hour",
+        )?;
+        let hourly = stmt
+            .query_map((&first, &last), |row| {
+                Ok(HourlyRecord {
+                    date: parse_date(row.get(0)?)?,
+                    app: row.get(1)?,
+                    hour: row.get(2)?,
+                    seconds: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut sessions = Vec::new();
+        if let Some((from, to)) = self.day_bounds(first_day, last_day)? {
+            let zone = self.zone()?;
+            let day_start = self.day_start()?;
+            let mut stmt = self.conn.prepare(
+                "SELECT a.name, s.started_at, s.ended_at FROM sessions s
+                JOIN apps a ON a.id = s.app_id
+                WHERE s.started_at >= ?1 AND s.started_at < ?2
+                ORDER BY s.started_at, a.id",
+            )?;
+            let rows = stmt.query_map((from, to), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+
+            for row in rows {
+                let (app, started_at, ended_at) = row?;
+                if let (Some(start), Some(end)) =
+                    (zone.datetime(started_at), zone.datetime(ended_at))
+                {
+                    sessions.push(SessionRecord {
+                        date: day_of(start, day_start),
+                        app,
+                        started_at: start,
+                        ended_at: end,
+                        seconds: (ended_at - started_at).max(0) as u64,
+                    });
+                }
+            }
         }
 
-        self.del_notif(name)?;
+        // The days older than the horizon are only kept in the totals of their month
+        let mut stmt = self.conn.prepare(
+            "SELECT m.month, a.name, m.seconds, m.foreground_seconds FROM usage_monthly m
+            JOIN apps a ON a.id = m.app_id
+            WHERE (?1 IS NULL OR m.month >= strftime('%Y-%m', ?1))
+            AND m.month <= strftime('%Y-%m', ?2)
+            ORDER BY m.month, a.id",
+        )?;
+        let monthly = stmt
+            .query_map((&first, &last), |row| {
+                Ok(MonthlyRecord {
+                    date: parse_date(format!("{}-01", row.get::<_, String>(0)?))?,
+                    app: row.get(1)?,
+                    seconds: row.get(2)?,
+                    foreground_seconds: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(Records {
+            usage,
+            hourly,
+            sessions,
+            monthly,
+        })
+    }
+
+    // Merges the imported days and hours into the recorded ones. The missing applications
+    // are created, and a dry run rolls everything back once the summary is computed.
+    pub fn import(&self, import: &Import, merge: Merge, dry_run: bool) -> Result<ImportSummary> {
+        let zone = self.zone()?;
+        let day_start = self.day_start()?;
+
+        let mut usage: BTreeMap<(&str, NaiveDate), (u64, u64)> = BTreeMap::new();
+        let mut hourly: BTreeMap<(&str, NaiveDate, u32), u64> = BTreeMap::new();
+        for record in &import.usage {
+            let value = usage.entry((&record.app, record.date)).or_default();
+            value.0 += record.seconds;
+            value.1 += record.foreground_seconds;
+        }
+        for record in &import.hourly {
+            *hourly
+                .entry((&record.app, record.date, record.hour))
+                .or_default() += record.seconds;
+        }
+
+        // The intervals are split at each hour so that they land in the right day and hour
+        for interval in &import.intervals {
+            let mut start = interval.start;
+            let end = interval.start + interval.seconds as i64;
+            while start < end {
+                let Some(datetime) = zone.datetime(start) else {
+                    break;
+                };
+                let into_hour = (datetime.minute() * 60 + datetime.second()) as i64;
+                let next = end.min(start + NUMBER_SECONDS_IN_HOUR - into_hour);
+                let seconds = (next - start) as u64;
+                let date = day_of(datetime, day_start);
+
+                let value = usage.entry((&interval.app, date)).or_default();
+                value.0 += seconds;
+                if interval.foreground {
+                    value.1 += seconds;
+                }
+                *hourly
+                    .entry((&interval.app, date, datetime.hour()))
+                    .or_default() += seconds;
+
+                start = next;
+            }
+        }
+
+        let mut summary = ImportSummary {
+            dry_run,
+            ..ImportSummary::default()
+        };
+
+        // The days older than the horizon are only kept in the totals of their month, which
+        // cannot be merged day by day, so they are left out
+        let horizon = self.daily_horizon()?;
+        usage.retain(|(_, date), _| {
+            if *date < horizon {
+                summary.old_days.insert(*date);
+            }
+            *date >= horizon
+        });
+        hourly.retain(|(_, date, _), _| {
+            if *date < horizon {
+                summary.old_days.insert(*date);
+            }
+            *date >= horizon
+        });
+
+        let tx = self.conn.unchecked_transaction()?;
+
+        let apps: BTreeSet<&str> = usage.keys().map(|(app, _)| *app).collect();
+        for app in apps {
+            // The history of an untracked application is kept, the imported ones are
+            // only tracked once added
+            if self.get_app_id(app)?.is_none() {
+                Self::create_app(&tx, app, false)?;
+                summary.new_apps.push(app.to_string());
+            }
+        }
+
+        for ((app, date), (seconds, foreground_seconds)) in usage {
+            let old = tx
+                .query_row(
+                    "SELECT u.seconds, u.foreground_seconds FROM usage u
+                    JOIN apps a ON a.id = u.app_id WHERE a.name = ?1 AND u.date = ?2",
+                    (app, date.to_string()),
+                    |row| Ok((row.get::<_, u64>(0)?, row.get::<_, u64>(1)?)),
+                )
+                .optional()?;
+            let (old_seconds, old_foreground) = old.unwrap_or((0, 0));
+            let new_seconds = merge.apply(old_seconds, seconds);
+            let new_foreground = merge.apply(old_foreground, foreground_seconds);
+
+            match old {
+                None => summary.days_created += 1,
+                Some(old) if old != (new_seconds, new_foreground) => summary.days_updated += 1,
+                Some(_) => continue,
+            }
+            summary.sec_added += new_seconds.saturating_sub(old_seconds);
+            summary.sec_removed += old_seconds.saturating_sub(new_seconds);
+
+            tx.execute(
+                "INSERT INTO usage (app_id, date, seconds, foreground_seconds)
+                SELECT id, ?2, ?3, ?4 FROM apps WHERE name = ?1
+                ON CONFLICT (app_id, date) DO UPDATE SET seconds = excluded.seconds,
+                foreground_seconds = excluded.foreground_seconds",
+                (app, date.to_string(), new_seconds, new_foreground),
+            )?;
+        }
+
+        for ((app, date, hour), seconds) in hourly {
+            let old = tx
+                .query_row(
+                    "SELECT h.seconds FROM usage_hourly h JOIN apps a ON a.id = h.app_id
+                    WHERE a.name = ?1 AND h.date = ?2 AND h.hour = ?3",
+                    (app, date.to_string(), hour),
+                    |row| row.get::<_, u64>(0),
+                )
+                .optional()?;
+            let new_seconds = merge.apply(old.unwrap_or(0), seconds);
+
+            match old {
+                None => summary.hours_created += 1,
+                Some(old) if old != new_seconds => summary.hours_updated += 1,
+                Some(_) => continue,
+            }
+
+            tx.execute(
+                "INSERT INTO usage_hourly (app_id, date, hour, seconds)
+                SELECT id, ?2, ?3, ?4 FROM apps WHERE name = ?1
+                ON CONFLICT (app_id, date, hour) DO UPDATE SET seconds = excluded.seconds",
+                (app, date.to_string(), hour, new_seconds),
+            )?;
+        }
+
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok(summary)
+    }
+
+    // The limit replaces the one of the application with the same number of minutes, period
+    // and days
+    pub fn add_notif(&self, notif: &Notif) -> Result<()> {
+        if !self.contain_names(&notif.app)? {
+            return Err(Error::usage("This application is not followed"));
+        }
+        if notif.warning_minutes > 0 && notif.warning_minutes >= notif.minutes {
+            return Err(Error::usage(
+                "The warning must be sent less minutes before the limit than the limit itself",
+            ));
+        }
+        if notif.days == Days::from_bits(0) {
+            return Err(Error::usage("The limit must apply on at least one day"));
+        }
+        if notif.period == Period::Week && notif.days != Days::ALL {
+            return Err(Error::usage("The days only apply to the daily limits"));
+        }
+        if notif.period == Period::Week && self.get_settings()?.storage_size < NUMBER_DAYS_IN_WEEK {
+            return Err(Error::usage(format!(
+                "The weekly limits need a storage of at least {} days",
+                NUMBER_DAYS_IN_WEEK
+            )));
+        }
+        match (notif.action, &notif.command) {
+            (Some(Action::Kill), _) if notif.app == SCREENTIME => {
+                return Err(Error::usage(format!(
+                    "The {} application has no process to kill, use a command instead",
+                    SCREENTIME
+                )));
+            }
+            (Some(Action::Command), None) => {
+                return Err(Error::usage("The command action needs a command"));
+            }
+            (Some(Action::Kill) | None, Some(_)) => {
+                return Err(Error::usage(
+                    "The command is only run by the command action",
+                ));
+            }
+            _ => (),
+        }
+
+        let message = notif
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|message| !message.is_empty());
         self.conn.execute(
-            "INSERT INTO notification (app, time) VALUES (?1, ?2)",
-            (name, &time),
+            "INSERT INTO notification (app, time, period, days, warning_minutes,
+            repeat_minutes, critical_after, message, action, command, grace_minutes)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            ON CONFLICT (app, period, days, time) DO UPDATE SET
+            warning_minutes = excluded.warning_minutes, repeat_minutes = excluded.repeat_minutes,
+            critical_after = excluded.critical_after, message = excluded.message,
+            action = excluded.action, command = excluded.command,
+            grace_minutes = excluded.grace_minutes",
+            params![
+                &notif.app,
+                notif.minutes,
+                notif.period.as_str(),
+                notif.days.bits(),
+                notif.warning_minutes,
+                notif.repeat_minutes,
+                notif.critical_after,
+                message,
+                notif.action.map(|action| action.as_str()),
+                &notif.command,
+                notif.grace_minutes,
+            ],
         )?;
 
         Ok(())
     }
 
-    pub fn del_notif(&self, name: &str) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM notification WHERE app = ?1", ((name),))?;
+    // Without a number of minutes, all the limits of the application are removed, otherwise
+    // the limits of this number of minutes whatever their period and days
+    pub fn del_notif(&self, name: &str, minutes: Option<u16>) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM notification_log WHERE notif_id IN
+            (SELECT id FROM notification WHERE app = ?1 AND (?2 IS NULL OR time = ?2))",
+            (name, minutes),
+        )?;
+        let deleted = tx.execute(
+            "DELETE FROM notification WHERE app = ?1 AND (?2 IS NULL OR time = ?2)",
+            (name, minutes),
+        )?;
+        if minutes.is_some() && deleted == 0 {
+            return Err(Error::usage(
+                "The notification you want to delete does not exist",
+            ));
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Removes the limit with the same minutes, period and days
+    pub fn del_limit(&self, notif: &Notif) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        let id = tx
+            .query_row(
+                "SELECT id FROM notification
+                WHERE app = ?1 AND time = ?2 AND period = ?3 AND days = ?4",
+                (
+                    &notif.app,
+                    notif.minutes,
+                    notif.period.as_str(),
+                    notif.days.bits(),
+                ),
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+        let Some(id) = id else {
+            return Err(Error::usage(
+                "The notification you want to delete does not exist",
+            ));
+        };
+        tx.execute("DELETE FROM notification_log WHERE notif_id = ?1", (id,))?;
+        tx.execute("DELETE FROM notification WHERE id = ?1", (id,))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_notifs(&self) -> Result<Vec<Notif>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM notification n ORDER BY n.app, n.period, n.time",
+            NOTIF_COLUMNS
+        ))?;
+        let rows = stmt.query_map(params![], |row| Ok(notif_from_row(row)?.1))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    // Time of the application on the days of the week before the date
+    fn get_time_week(&self, name: &str, date: NaiveDate) -> Result<u64> {
+        let days = (date - Period::Week.start(date)).num_days() as u16;
+        if days == 0 {
+            return Ok(0);
+        }
+
+        // A month total would count days outside of the week
+        Ok(self
+            .get_time_app(name, date - Duration::days(1), days)?
+            .iter()
+            .filter(|value| !value.month)
+            .map(|value| value.sec_total)
+            .sum())
+    }
+
+    // Time used during the period of the limit that contains the date, today_value being
+    // the time of the date
+    fn get_time_period(&self, notif: &Notif, today_value: u64, date: NaiveDate) -> Result<u64> {
+        match notif.period {
+            Period::Day => Ok(today_value),
+            Period::Week => Ok(today_value + self.get_time_week(&notif.app, date)?),
+        }
+    }
+
+    // A notification is sent once per period as soon as the time reaches the limit, even if
+    // updates were missed or the limit was lowered below the time already spent
+    fn check_notif(&self, names: &[String], values: &[u64], date: NaiveDate) -> Result<()> {
+        // A notification that cannot be shown must not prevent the other ones, it is sent
+        // again at the next update since it is not logged
+        let mut sent = Ok(());
+        for due in self.due_notifications(names, values, date)? {
+            let name = &names[due.index];
+            let limit = due.threshold as u64 * NUMBER_SECONDS_IN_MINUTE;
+            let when = match due.period {
+                Period::Day => "",
+                Period::Week => " this week",
+            };
+            let (summary, body) = match due.kind {
+                NotifKind::Warning => (
+                    format!(
+                        "{} remaining for {}{}",
+                        Time::new(limit.saturating_sub(due.used)),
+                        name,
+                        when
+                    ),
+                    format!(
+                        "You will soon reach the limit of {} set for {}",
+                        Time::new(limit),
+                        name
+                    ),
+                ),
+                _ => (
+                    match due.reminder {
+                        0 => format!("Time passed for {}{}", name, when),
+                        n => format!("Time passed for {}{} (reminder {})", name, when, n),
+                    },
+                    format!(
+                        "It has been {} for you to use {}{}. You have exceeded the limit of {}",
+                        Time::new(due.used),
+                        name,
+                        when,
+                        Time::new(limit)
+                    ),
+                ),
+            };
+            match Notification::new()
+                .summary(&summary)
+                .body(due.message.as_deref().unwrap_or(&body))
+                .appname("Time App Tracker")
+                .urgency(due.urgency)
+                .show()
+            {
+                Ok(_) => {
+                    self.log_notification(due.id, due.period.start(date), due.kind, due.reminder)?
+                }
+                Err(e) => sent = sent.and(Err(Error::from(e))),
+            }
+        }
+        sent
+    }
+
+    // Notifications of the limits, in minutes, that are reached or close to be and that
+    // have not been sent yet during their period
+    fn due_notifications(
+        &self,
+        names: &[String],
+        values: &[u64],
+        date: NaiveDate,
+    ) -> Result<Vec<DueNotification>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, w.reminders, l.reminders FROM notification n
+            LEFT JOIN notification_log w ON w.notif_id = n.id AND w.kind = 'warning'
+            AND w.date = CASE n.period WHEN 'week' THEN ?3 ELSE ?2 END
+            LEFT JOIN notification_log l ON l.notif_id = n.id AND l.kind = 'limit'
+            AND l.date = CASE n.period WHEN 'week' THEN ?3 ELSE ?2 END
+            WHERE n.app = ?1 ORDER BY n.period, n.time",
+            NOTIF_COLUMNS
+        ))?;
+
+        let mut due = Vec::new();
+        for (index, name) in names.iter().enumerate() {
+            let rows = stmt.query_map(
+                (name, date.to_string(), Period::Week.start(date).to_string()),
+                |row| {
+                    Ok((
+                        notif_from_row(row)?,
+                        row.get::<_, Option<u16>>(12)?,
+                        row.get::<_, Option<u16>>(13)?,
+                    ))
+                },
+            )?;
+
+            for row in rows {
+                let ((id, notif), warned, sent) = row?;
+                if !notif.applies(date) {
+                    continue;
+                }
+
+                let used = self.get_time_period(&notif, values[index], date)?;
+                let limit = notif.minutes as u64 * NUMBER_SECONDS_IN_MINUTE;
+                let warning =
+                    limit.saturating_sub(notif.warning_minutes as u64 * NUMBER_SECONDS_IN_MINUTE);
+                let mut notification = DueNotification {
+                    index,
+                    id,
+                    threshold: notif.minutes,
+                    period: notif.period,
+                    used,
+                    kind: NotifKind::Limit,
+                    reminder: 0,
+                    urgency: Urgency::Normal,
+                    message: notif.message,
+                };
+
+                // The warning is no longer useful once the limit is reached
+                if used < limit {
+                    if notif.warning_minutes > 0 && used >= warning && warned.is_none() {
+                        notification.kind = NotifKind::Warning;
+                        due.push(notification);
+                    }
+                    continue;
+                }
+
+                // Only the last reminder is sent when several of them were missed
+                notification.reminder = match sent {
+                    None => 0,
+                    Some(_) if notif.repeat_minutes == 0 => continue,
+                    Some(sent) => {
+                        let minutes = (used - limit) / NUMBER_SECONDS_IN_MINUTE;
+                        let reminder = (minutes / notif.repeat_minutes as u64) as u16;
+                        if reminder <= sent {
+                            continue;
+                        }
+                        reminder
+                    }
+                };
+
+                notification.urgency = match notif.critical_after {
+                    Some(n) if notification.reminder > n => Urgency::Critical,
+                    _ => Urgency::Normal,
+                };
+                due.push(notification);
+            }
+        }
+        Ok(due)
+    }
+
+    // The date is the first day of the period of the notification
+    fn log_notification(
+        &self,
+        id: i64,
+        date: NaiveDate,
+        kind: NotifKind,
+        reminder: u16,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO notification_log (notif_id, date, kind, sent_at, reminders)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (notif_id, date, kind) DO UPDATE SET
+            sent_at = excluded.sent_at, reminders = excluded.reminders",
+            (
+                id,
+                date.to_string(),
+                kind.as_str(),
+                Utc::now().timestamp(),
+                reminder,
+            ),
+        )?;
+        Ok(())
+    }
+
+    // The actions of the application are suspended for the day, the reason is kept
+    pub fn snooze(&self, name: &str, reason: &str) -> Result<()> {
+        if !self.contain_names(name)? {
+            return Err(Error::usage("This application is not followed"));
+        }
+        if reason.trim().is_empty() {
+            return Err(Error::usage("the reason must not be empty"));
+        }
+
+        self.conn.execute(
+            "INSERT INTO snooze (app, date, reason, granted_at) VALUES (?1, ?2, ?3, ?4)",
+            (
+                name,
+                self.today()?.to_string(),
+                reason.trim(),
+                Utc::now().timestamp(),
+            ),
+        )?;
         Ok(())
     }
 
-    fn check_notif(&self, names: &[String], values: &[u16]) -> Result<()> {
-        for (i, name) in names.iter().enumerate() {
-            let mut stmt = self.conn.prepare(&format!(
-                "SELECT 1 FROM notification WHERE app = '{}' AND time = {} LIMIT 1",
-                &name, values[i]
-            ))?;
-            let mut rows = stmt.query_map(params![], |_| {
-                Notification::new()
-                    .summary(&format!("Time passed for {}", &name))
+    // Reason of the last snooze of the application on this day
+    fn get_snooze(&self, name: &str, date: NaiveDate) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT reason FROM snooze WHERE app = ?1 AND date = ?2
+                ORDER BY granted_at DESC, id DESC LIMIT 1",
+                (name, date.to_string()),
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    // Actions of the limits exceeded by the applications running during the update. The
+    // warning is sent first, and the action is taken at each update once the grace period
+    // has passed unless the application is snoozed for the day
+    fn due_enforcements(
+        &self,
+        names: &[String],
+        running: &[bool],
+        values: &[u64],
+        date: NaiveDate,
+        now: i64,
+    ) -> Result<Vec<DueEnforcement>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, g.sent_at FROM notification n
+            LEFT JOIN notification_log g ON g.notif_id = n.id AND g.kind = 'grace'
+            AND g.date = CASE n.period WHEN 'week' THEN ?3 ELSE ?2 END
+            WHERE n.app = ?1 AND n.action IS NOT NULL ORDER BY n.period, n.time",
+            NOTIF_COLUMNS
+        ))?;
+
+        let mut due = Vec::new();
+        for (index, name) in names.iter().enumerate() {
+            if !running[index] || self.get_snooze(name, date)?.is_some() {
+                continue;
+            }
+
+            let rows = stmt.query_map(
+                (name, date.to_string(), Period::Week.start(date).to_string()),
+                |row| Ok((notif_from_row(row)?, row.get::<_, Option<i64>>(12)?)),
+            )?;
+            for row in rows {
+                let ((id, notif), warned_at) = row?;
+                let used = self.get_time_period(&notif, values[index], date)?;
+                if !notif.applies(date) || used < notif.minutes as u64 * NUMBER_SECONDS_IN_MINUTE {
+                    continue;
+                }
+
+                let grace = notif.grace_minutes as i64 * NUMBER_SECONDS_IN_MINUTE as i64;
+                let warned_at = match warned_at {
+                    Some(warned_at) => warned_at,
+                    None => {
+                        due.push(DueEnforcement {
+                            index,
+                            id,
+                            grace: true,
+                            notif: notif.clone(),
+                        });
+                        now
+                    }
+                };
+                if now >= warned_at + grace {
+                    due.push(DueEnforcement {
+                        index,
+                        id,
+                        grace: false,
+                        notif,
+                    });
+                }
+            }
+        }
+        Ok(due)
+    }
+
+    fn enforce_limits(
+        &self,
+        tracked: &Tracked,
+        running: &[bool],
+        values: &[u64],
+        date: NaiveDate,
+    ) -> Result<()> {
+        let names: Vec<String> = tracked.apps.iter().map(|app| app.name.clone()).collect();
+        let now = Utc::now().timestamp();
+        let mut processes = None;
+        // A warning that cannot be shown must not prevent the actions
+        let mut warned = Ok(());
+
+        for due in self.due_enforcements(&names, running, values, date, now)? {
+            let name = &names[due.index];
+            let notif = &due.notif;
+            let start = notif.period.start(date);
+            if due.grace {
+                let grace = Time::new(notif.grace_minutes as u64 * NUMBER_SECONDS_IN_MINUTE);
+                let summary = match notif.action {
+                    Some(Action::Kill) => format!("{} will be closed in {}", name, grace),
+                    _ => format!("The limit of {} will be enforced in {}", name, grace),
+                };
+                // The grace period starts even if the warning cannot be shown
+                self.log_notification(due.id, start, NotifKind::Grace, 0)?;
+                warned = Notification::new()
+                    .summary(&summary)
                     .body(&format!(
-                        "It has been {} for you to use {}. You have exceeded the set limit",
-                        Time::new(values[i]),
-                        &name
+                        "You have exceeded the limit of {} set for {}. Use `time_app_tracker notif snooze {} <reason>` to allow it today",
+                        Time::new(notif.minutes as u64 * NUMBER_SECONDS_IN_MINUTE),
+                        name,
+                        name
                     ))
                     .appname("Time App Tracker")
+                    .urgency(Urgency::Critical)
                     .show()
-                    .expect("Impossible d'envoyer de notification");
-                Ok(())
-            })?;
+                    .map_err(Error::from)
+                    .and(warned);
+                continue;
+            }
+
+            match (notif.action, &notif.command) {
+                (Some(Action::Kill), _) => {
+                    if processes.is_none() {
+                        processes = Some(ProcessTable::scan()?);
+                    }
+                    let uid = current_uid().map_err(Error::Enforcement)?;
+                    let pids: Vec<u32> = processes
+                        .iter()
+                        .flat_map(|table| table.matching(&tracked.apps[due.index]))
+                        .filter(|process| process.uid() == uid)
+                        .map(Process::pid)
+                        .filter(|pid| *pid != std::process::id())
+                        .collect();
+                    terminate(&pids).map_err(Error::Enforcement)?;
+                }
+                (Some(Action::Command), Some(command)) => {
+                    run_command(command).map_err(Error::Enforcement)?;
+                }
+                _ => continue,
+            }
+            self.log_notification(due.id, start, NotifKind::Enforcement, 0)?;
+        }
+        warned
+    }
 
-            while let Some(Ok(())) = rows.next() {}
+    // Each limit with the time used today or this week and the time remaining
+    pub fn get_budgets(&self) -> Result<Vec<Budget>> {
+        let today = self.today()?;
+        let mut budgets = Vec::new();
+        for notif in self.get_notifs()? {
+            let today_value = self.get_values(std::slice::from_ref(&notif.app), today)?[0];
+            let used = self.get_time_period(&notif, today_value, today)?;
+            let remaining = notif.applies(today).then(|| {
+                Time::new((notif.minutes as u64 * NUMBER_SECONDS_IN_MINUTE).saturating_sub(used))
+            });
+            let snoozed = self.get_snooze(&notif.app, today)?;
+            budgets.push(Budget {
+                notif,
+                used: Time::new(used),
+                remaining,
+                snoozed,
+            });
         }
-        Ok(())
+        Ok(budgets)
     }
 
-    pub fn print_notif(&self) -> Result<()> {
-        let mut stmt = self.conn.prepare("SELECT app, time FROM notification")?;
-        let mut rows = stmt.query_map(params![], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, u16>(1)?))
-        })?;
+    pub fn print_notif(&self, output: Output) -> Result<()> {
+        let entries = self.get_budgets()?;
 
-        println!("\tList of notifications :");
-        while let Some(Ok((app, time))) = rows.next() {
-            println!("{} => {}", app, Time::new(time));
+        match output {
+            Output::Plain => {
+                println!("\tList of notifications :");
+                for entry in &entries {
+                    println!("{}", entry);
+                }
+                println!();
+            }
+            Output::Json => print_json(&entries)?,
+            Output::Table | Output::Csv => print_rows(output, &entries)?,
         }
-        println!();
 
         Ok(())
     }
@@ -0,0 +1,97 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+
+use crate::error::Result;
+
+// Format of the data printed by the queries, plain is the human readable text
+#[derive(Clone, Copy, PartialEq)]
+pub enum Output {
+    Plain,
+    Table,
+    Json,
+    Csv,
+}
+
+impl Output {
+    pub fn parse(value: &str) -> Option<Output> {
+        match value {
+            "plain" => Some(Output::Plain),
+            "table" => Some(Output::Table),
+            "json" => Some(Output::Json),
+            "csv" => Some(Output::Csv),
+            _ => None,
+        }
+    }
+}
+
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, value).map_err(io::Error::from)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+// Prints flat rows as a table or as CSV, the columns are the fields of the first row
+pub fn print_rows<T: Serialize>(output: Output, rows: &[T]) -> Result<()> {
+    let rows = rows
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<std::result::Result<Vec<Value>, _>>()
+        .map_err(io::Error::from)?;
+    let Some(Value::Object(first)) = rows.first() else {
+        return Ok(());
+    };
+    let headers: Vec<String> = first.keys().cloned().collect();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| headers.iter().map(|key| cell(&row[key])).collect())
+        .collect();
+
+    if output == Output::Csv {
+        let mut writer = csv::Writer::from_writer(io::stdout());
+        writer.write_record(&headers).map_err(io::Error::from)?;
+        for row in &cells {
+            writer.write_record(row).map_err(io::Error::from)?;
+        }
+        writer.flush()?;
+        return Ok(());
+    }
+
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .fold(header.chars().count(), usize::max)
+        })
+        .collect();
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", table_line(&headers, &widths))?;
+    writeln!(stdout, "{}", table_line(&separator, &widths))?;
+    for row in &cells {
+        writeln!(stdout, "{}", table_line(row, &widths))?;
+    }
+    Ok(())
+}
+
+fn table_line(values: &[String], widths: &[usize]) -> String {
+    let values: Vec<String> = values
+        .iter()
+        .zip(widths)
+        .map(|(value, width)| format!("{:width$}", value))
+        .collect();
+    values.join("  ").trim_end().to_string()
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
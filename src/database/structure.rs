@@ -1,30 +1,60 @@
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+use serde::{Serialize, Serializer};
+use std::collections::BTreeSet;
 
-const NUMBER_MINUTES_IN_HOUR: u16 = 60;
+const NUMBER_SECONDS_IN_MINUTE: u64 = 60;
+const NUMBER_SECONDS_IN_HOUR: u64 = 60 * NUMBER_SECONDS_IN_MINUTE;
+pub const NUMBER_HOURS_IN_DAY: usize = 24;
+pub const DEFAULT_GRACE_MINUTES: u16 = 5;
+const TIMELINE_LEVELS: [char; 5] = [' ', '░', '▒', '▓', '█'];
 
 #[derive(PartialEq)]
 pub enum Type {
     Day,
+    Group,
     App(String),
 }
 
 #[derive(Default)]
 pub struct Time {
-    hour: u16,
-    min: u16,
+    hour: u64,
+    min: u64,
+    sec: u64,
 }
 
 impl Time {
-    pub fn new(mins: u16) -> Time {
+    pub fn new(secs: u64) -> Time {
         Time {
-            hour: mins / NUMBER_MINUTES_IN_HOUR,
-            min: mins % NUMBER_MINUTES_IN_HOUR,
+            hour: secs / NUMBER_SECONDS_IN_HOUR,
+            min: secs % NUMBER_SECONDS_IN_HOUR / NUMBER_SECONDS_IN_MINUTE,
+            sec: secs % NUMBER_SECONDS_IN_MINUTE,
         }
     }
+
+    pub fn total_seconds(&self) -> u64 {
+        self.hour * NUMBER_SECONDS_IN_HOUR + self.min * NUMBER_SECONDS_IN_MINUTE + self.sec
+    }
 }
 
+// A time is serialized as its number of seconds
+impl Serialize for Time {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.total_seconds())
+    }
+}
+
+// The alternate flag (`{:#}`) also displays the seconds
 impl std::fmt::Display for Time {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if f.alternate() {
+            if self.hour == 0 && self.min == 0 {
+                return write!(f, "{}s", self.sec);
+            } else if self.hour == 0 {
+                return write!(f, "{}m{:02}s", self.min, self.sec);
+            }
+            return write!(f, "{}h{:02}m{:02}s", self.hour, self.min, self.sec);
+        }
+
         if self.hour == 0 {
             return write!(f, "{}m", self.min);
         } else if self.min == 0 {
@@ -36,21 +66,48 @@ impl std::fmt::Display for Time {
     }
 }
 
+#[derive(Serialize)]
 pub struct TimeApp {
     pub name: String,
+    #[serde(skip)]
     pub time: Time,
     pub date: NaiveDate,
-    pub min_total: u16,
+    #[serde(rename = "seconds")]
+    pub sec_total: u64,
+    // Time during which the application owned the focused window
+    #[serde(skip)]
+    pub foreground: Time,
+    #[serde(rename = "foreground_seconds")]
+    pub foreground_total: u64,
+    // Total of the month of the date, once the days have been rolled up
+    pub month: bool,
 }
 
 impl TimeApp {
-    pub fn new(name: String, date: NaiveDate, mins: u16) -> TimeApp {
+    pub fn new(name: String, date: NaiveDate, secs: u64, foreground_secs: u64) -> TimeApp {
         TimeApp {
             name,
-            time: Time::new(mins),
+            time: Time::new(secs),
             date,
-            min_total: mins,
+            sec_total: secs,
+            foreground: Time::new(foreground_secs),
+            foreground_total: foreground_secs,
+            month: false,
+        }
+    }
+
+    pub fn new_month(name: String, date: NaiveDate, secs: u64, foreground_secs: u64) -> TimeApp {
+        TimeApp {
+            month: true,
+            ..TimeApp::new(name, date, secs, foreground_secs)
+        }
+    }
+
+    pub fn date_label(&self) -> String {
+        if self.month {
+            return format!("{} (month)", self.date.format("%Y-%m"));
         }
+        self.date.to_string()
     }
 }
 
@@ -62,8 +119,8 @@ pub struct ListTimeApp {
 
 impl ListTimeApp {
     pub fn new(type_data: Type, mut values: Vec<TimeApp>, date: NaiveDate) -> ListTimeApp {
-        if type_data == Type::Day {
-            values.sort_unstable_by_key(|item| (item.min_total));
+        if !matches!(type_data, Type::App(_)) {
+            values.sort_unstable_by_key(|item| item.sec_total);
             values.reverse();
         }
         ListTimeApp {
@@ -72,6 +129,38 @@ impl ListTimeApp {
             date,
         }
     }
+
+    // The days rolled up are displayed as their whole month
+    fn date_label(&self) -> String {
+        if self.values.iter().any(|v| v.month) {
+            return format!("{} (month)", self.date.format("%Y-%m"));
+        }
+        self.date.to_string()
+    }
+}
+
+impl ListTimeApp {
+    // The foreground time is only displayed when it has been measured
+    fn format_value(&self, label: String, value: &TimeApp, seconds: bool) -> String {
+        if self.values.iter().any(|v| v.foreground_total > 0) {
+            format!(
+                "{} : {} (foreground {})\n",
+                label,
+                format_time(&value.time, seconds),
+                format_time(&value.foreground, seconds)
+            )
+        } else {
+            format!("{} : {}\n", label, format_time(&value.time, seconds))
+        }
+    }
+}
+
+fn format_time(time: &Time, seconds: bool) -> String {
+    if seconds {
+        format!("{:#}", time)
+    } else {
+        format!("{}", time)
+    }
 }
 
 impl std::fmt::Display for ListTimeApp {
@@ -79,9 +168,18 @@ impl std::fmt::Display for ListTimeApp {
         match &self.type_data {
             Type::Day => {
                 let mut output = String::new();
-                output.push_str(&format!("\tApplication time for {} :\n", self.date));
+                output.push_str(&format!("\tApplication time for {} :\n", self.date_label()));
                 for v in &self.values {
-                    output.push_str(&format!("{} : {}\n", v.name, v.time));
+                    output.push_str(&self.format_value(v.name.clone(), v, f.alternate()));
+                }
+
+                write!(f, "{}", output)
+            }
+            Type::Group => {
+                let mut output = String::new();
+                output.push_str(&format!("\tGroup time for {} :\n", self.date_label()));
+                for v in &self.values {
+                    output.push_str(&self.format_value(v.name.clone(), v, f.alternate()));
                 }
 
                 write!(f, "{}", output)
@@ -90,14 +188,15 @@ impl std::fmt::Display for ListTimeApp {
                 let mut output = String::new();
                 output.push_str(&format!("\tTime for {} :\n", name));
                 for v in &self.values {
-                    output.push_str(&format!("{} : {}\n", v.date, v.time));
+                    output.push_str(&self.format_value(v.date_label(), v, f.alternate()));
                 }
 
-                output.push_str(&format!(
-                    "\n\tStats of time for {} :\n{}\n",
-                    name,
-                    Stat::new(&self.values)
-                ));
+                let stat = Stat::new(&self.values);
+                if f.alternate() {
+                    output.push_str(&format!("\n\tStats of time for {} :\n{:#}\n", name, stat));
+                } else {
+                    output.push_str(&format!("\n\tStats of time for {} :\n{}\n", name, stat));
+                }
 
                 write!(f, "{}", output)
             }
@@ -105,7 +204,7 @@ impl std::fmt::Display for ListTimeApp {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Serialize)]
 pub struct Stat {
     pub max: Time,
     pub min: Time,
@@ -114,25 +213,28 @@ pub struct Stat {
 }
 
 impl Stat {
+    // The totals of the rolled-up months are not the time of a single day and may cover
+    // days outside of the requested ones, so they are left out
     pub fn new(values: &[TimeApp]) -> Stat {
+        let values: Vec<&TimeApp> = values.iter().filter(|v| !v.month).collect();
         if values.is_empty() {
             return Stat::default();
             // return Stat { max : Time::new(0), min : Time::new(0), sum : Time::new(0), mean : Time::new(0) };
         }
 
-        let mut sum = values[0].min_total;
-        let mut min = values[0].min_total;
-        let mut max = values[0].min_total;
+        let mut sum = values[0].sec_total;
+        let mut min = values[0].sec_total;
+        let mut max = values[0].sec_total;
 
         for v in values.iter().skip(1) {
-            sum += v.min_total;
+            sum += v.sec_total;
 
-            if min > v.min_total {
-                min = v.min_total;
+            if min > v.sec_total {
+                min = v.sec_total;
             }
 
-            if max < v.min_total {
-                max = v.min_total;
+            if max < v.sec_total {
+                max = v.sec_total;
             }
         }
 
@@ -140,13 +242,20 @@ impl Stat {
             max: Time::new(max),
             min: Time::new(min),
             sum: Time::new(sum),
-            mean: Time::new(sum / values.len() as u16),
+            mean: Time::new(sum / values.len() as u64),
         }
     }
 }
 
 impl std::fmt::Display for Stat {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if f.alternate() {
+            return write!(
+                f,
+                "Max : {:#}\nMin : {:#}\nSum : {:#}\nMean: {:#}",
+                self.max, self.min, self.sum, self.mean
+            );
+        }
         write!(
             f,
             "Max : {}\nMin : {}\nSum : {}\nMean: {}",
@@ -154,3 +263,529 @@ impl std::fmt::Display for Stat {
         )
     }
 }
+
+// Continuous run of an application, from the first to the last update it was seen running
+#[derive(Serialize)]
+pub struct Session {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    #[serde(skip)]
+    pub time: Time,
+    #[serde(rename = "seconds")]
+    pub sec_total: u64,
+}
+
+impl Session {
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime) -> Session {
+        let secs = (end - start).num_seconds().max(0) as u64;
+        Session {
+            start,
+            end,
+            time: Time::new(secs),
+            sec_total: secs,
+        }
+    }
+}
+
+impl std::fmt::Display for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if f.alternate() {
+            return write!(
+                f,
+                "{} -> {} : {:#}",
+                self.start.format("%Y-%m-%d %H:%M:%S"),
+                self.end.format("%H:%M:%S"),
+                self.time
+            );
+        }
+        write!(
+            f,
+            "{} -> {} : {}",
+            self.start.format("%Y-%m-%d %H:%M"),
+            self.end.format("%H:%M"),
+            self.time
+        )
+    }
+}
+
+pub struct ListSession {
+    name: String,
+    values: Vec<Session>,
+    number_days: u16,
+}
+
+impl ListSession {
+    pub fn new(name: String, values: Vec<Session>, number_days: u16) -> ListSession {
+        ListSession {
+            name,
+            values,
+            number_days,
+        }
+    }
+}
+
+impl std::fmt::Display for ListSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut output = String::new();
+        output.push_str(&format!("\tSessions for {} :\n", self.name));
+        for v in &self.values {
+            output.push_str(&format!("{}\n", format_session(v, f.alternate())));
+        }
+
+        let longest = Time::new(self.values.iter().map(|v| v.sec_total).max().unwrap_or(0));
+        output.push_str(&format!("\n\tStats of sessions for {} :\n", self.name));
+        output.push_str(&format!("Launches : {}\n", self.values.len()));
+        output.push_str(&format!(
+            "Launches per day : {:.1}\n",
+            self.values.len() as f64 / self.number_days.max(1) as f64
+        ));
+        output.push_str(&format!(
+            "Longest : {}\n",
+            format_time(&longest, f.alternate())
+        ));
+
+        write!(f, "{}", output)
+    }
+}
+
+fn format_session(session: &Session, seconds: bool) -> String {
+    if seconds {
+        format!("{:#}", session)
+    } else {
+        format!("{}", session)
+    }
+}
+
+// Time spent in an application for each hour of a day
+#[derive(Serialize)]
+pub struct TimelineApp {
+    pub name: String,
+    pub hours: [u64; NUMBER_HOURS_IN_DAY],
+}
+
+#[derive(Serialize)]
+pub struct Timeline {
+    pub date: NaiveDate,
+    // Hour at which the day starts, displayed first
+    pub first_hour: usize,
+    pub values: Vec<TimelineApp>,
+}
+
+impl Timeline {
+    // Hours of the day in the order they are displayed
+    pub fn hours(&self) -> impl Iterator<Item = usize> + use<> {
+        let first_hour = self.first_hour;
+        (0..NUMBER_HOURS_IN_DAY).map(move |i| (first_hour + i) % NUMBER_HOURS_IN_DAY)
+    }
+}
+
+// Character representing the share of an hour spent in an application
+pub fn timeline_level(seconds: u64) -> char {
+    if seconds == 0 {
+        return TIMELINE_LEVELS[0];
+    }
+
+    let quarters = (seconds * 4).div_ceil(NUMBER_SECONDS_IN_HOUR) as usize;
+    TIMELINE_LEVELS[quarters.clamp(1, TIMELINE_LEVELS.len() - 1)]
+}
+
+impl std::fmt::Display for Timeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let width = self
+            .values
+            .iter()
+            .map(|v| v.name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut output = String::new();
+        output.push_str(&format!("\tTimeline for {} :\n", self.date));
+        output.push_str(&format!("{:width$}  ", ""));
+        for hour in self.hours().step_by(6) {
+            output.push_str(&format!("{:<6}", hour));
+        }
+        output.push('\n');
+
+        for v in &self.values {
+            output.push_str(&format!("{:width$} |", v.name));
+            output.extend(self.hours().map(|h| timeline_level(v.hours[h])));
+            output.push_str("|\n");
+        }
+
+        write!(f, "{}", output)
+    }
+}
+
+// Period over which the time of a limit is counted, a week starts on Monday
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Day,
+    Week,
+}
+
+impl Period {
+    pub fn parse(value: &str) -> Option<Period> {
+        match value {
+            "day" => Some(Period::Day),
+            "week" => Some(Period::Week),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+        }
+    }
+
+    // First day of the period containing the date
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date.week(Weekday::Mon).first_day(),
+        }
+    }
+}
+
+// Days of the week on which a daily limit applies, the bit 0 is Monday
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Days(u8);
+
+impl Days {
+    pub const ALL: Days = Days(0b111_1111);
+    const WEEKDAYS: Days = Days(0b001_1111);
+    const WEEKENDS: Days = Days(0b110_0000);
+
+    pub fn from_bits(bits: u8) -> Days {
+        Days(bits & Days::ALL.0)
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    // A list separated by commas of days like mon or sunday, weekdays, weekends or all
+    pub fn parse(value: &str) -> Option<Days> {
+        let mut bits = 0;
+        for day in value.split(',').map(str::trim) {
+            bits |= match day {
+                "all" => Days::ALL.0,
+                "weekdays" => Days::WEEKDAYS.0,
+                "weekends" => Days::WEEKENDS.0,
+                _ => 1 << day.parse::<Weekday>().ok()?.num_days_from_monday(),
+            };
+        }
+        Some(Days(bits))
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.0 & 1 << date.weekday().num_days_from_monday() != 0
+    }
+}
+
+impl std::fmt::Display for Days {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Days::ALL => return write!(f, "all"),
+            Days::WEEKDAYS => return write!(f, "weekdays"),
+            Days::WEEKENDS => return write!(f, "weekends"),
+            _ => (),
+        }
+
+        let mut day = Weekday::Mon;
+        let mut names = Vec::new();
+        for i in 0..7 {
+            if self.0 & 1 << i != 0 {
+                names.push(day.to_string().to_lowercase());
+            }
+            day = day.succ();
+        }
+        write!(f, "{}", names.join(","))
+    }
+}
+
+impl Serialize for Days {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+// What is done to an application that is still used past its limit once the grace period
+// has passed
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    // Its processes are terminated, then killed if they do not exit
+    Kill,
+    // The command of the limit is run
+    Command,
+}
+
+impl Action {
+    pub fn parse(value: &str) -> Option<Action> {
+        match value {
+            "kill" => Some(Action::Kill),
+            "command" => Some(Action::Command),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Kill => "kill",
+            Action::Command => "command",
+        }
+    }
+}
+
+// Limit of the use of an application over a day or a week, several limits can be set for
+// the same one
+#[derive(Clone, Serialize)]
+pub struct Notif {
+    pub app: String,
+    pub minutes: u16,
+    pub period: Period,
+    // A daily limit only applies on these days
+    pub days: Days,
+    // A warning is sent this number of minutes before the limit, 0 disables it
+    pub warning_minutes: u16,
+    // After the limit, a reminder is sent every repeat_minutes of use if it is not 0
+    pub repeat_minutes: u16,
+    // The reminders beyond this number are critical
+    pub critical_after: Option<u16>,
+    // Body of the notification instead of the default one
+    pub message: Option<String>,
+    pub action: Option<Action>,
+    // Command run by the command action
+    pub command: Option<String>,
+    // Minutes between the warning of the action and the action itself
+    pub grace_minutes: u16,
+}
+
+impl Notif {
+    pub fn new(app: &str, minutes: u16) -> Notif {
+        Notif {
+            app: app.to_string(),
+            minutes,
+            period: Period::Day,
+            days: Days::ALL,
+            warning_minutes: 0,
+            repeat_minutes: 0,
+            critical_after: None,
+            message: None,
+            action: None,
+            command: None,
+            grace_minutes: DEFAULT_GRACE_MINUTES,
+        }
+    }
+
+    pub fn applies(&self, date: NaiveDate) -> bool {
+        self.period == Period::Week || self.days.contains(date)
+    }
+}
+
+impl std::fmt::Display for Notif {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let minutes = |minutes: u16| Time::new(minutes as u64 * NUMBER_SECONDS_IN_MINUTE);
+
+        write!(f, "{} => {}", self.app, minutes(self.minutes))?;
+        if self.period == Period::Week {
+            write!(f, " per week")?;
+        } else if self.days != Days::ALL {
+            write!(f, " on {}", self.days)?;
+        }
+        if self.warning_minutes > 0 {
+            write!(f, ", warning {} before", minutes(self.warning_minutes))?;
+        }
+        if self.repeat_minutes > 0 {
+            write!(f, ", every {}", minutes(self.repeat_minutes))?;
+        }
+        if let Some(critical_after) = self.critical_after {
+            write!(f, ", critical after {} reminders", critical_after)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ", \"{}\"", message)?;
+        }
+        match (self.action, &self.command) {
+            (Some(Action::Kill), _) => {
+                write!(f, ", killed after {}", minutes(self.grace_minutes))?;
+            }
+            (Some(Action::Command), Some(command)) => {
+                write!(
+                    f,
+                    ", runs `{}` after {}",
+                    command,
+                    minutes(self.grace_minutes)
+                )?;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+// Limit with the time used during its current period, nothing remains of a daily limit
+// that does not apply today
+#[derive(Serialize)]
+pub struct Budget {
+    #[serde(flatten)]
+    pub notif: Notif,
+    pub used: Time,
+    pub remaining: Option<Time>,
+    // Reason given to suspend the actions of the application today
+    pub snoozed: Option<String>,
+}
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.remaining {
+            Some(remaining) => write!(
+                f,
+                "{} : {} used, {} remaining",
+                self.notif, self.used, remaining
+            )?,
+            None => write!(f, "{} : not today", self.notif)?,
+        }
+        if let Some(reason) = &self.snoozed {
+            write!(f, ", snoozed today : {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+// Rows of the database as they are exported
+#[derive(Serialize)]
+pub struct UsageRecord {
+    pub date: NaiveDate,
+    pub app: String,
+    pub minutes: u64,
+    pub seconds: u64,
+    pub foreground_seconds: u64,
+}
+
+#[derive(Serialize)]
+pub struct HourlyRecord {
+    pub date: NaiveDate,
+    pub app: String,
+    pub hour: u32,
+    pub seconds: u64,
+}
+
+#[derive(Serialize)]
+pub struct SessionRecord {
+    // Day of the start of the session
+    pub date: NaiveDate,
+    pub app: String,
+    pub started_at: NaiveDateTime,
+    pub ended_at: NaiveDateTime,
+    pub seconds: u64,
+}
+
+// Time of the days of a month that were rolled up, dated by the first day of the month
+#[derive(Serialize)]
+pub struct MonthlyRecord {
+    pub date: NaiveDate,
+    pub app: String,
+    pub seconds: u64,
+    pub foreground_seconds: u64,
+}
+
+#[derive(Serialize)]
+pub struct Records {
+    pub usage: Vec<UsageRecord>,
+    pub hourly: Vec<HourlyRecord>,
+    pub sessions: Vec<SessionRecord>,
+    pub monthly: Vec<MonthlyRecord>,
+}
+
+// How the imported time is merged with the time already recorded for the same day
+#[derive(Clone, Copy)]
+pub enum Merge {
+    Add,
+    Max,
+    Replace,
+}
+
+impl Merge {
+    pub fn parse(value: &str) -> Option<Merge> {
+        match value {
+            "add" => Some(Merge::Add),
+            "max" => Some(Merge::Max),
+            "replace" => Some(Merge::Replace),
+            _ => None,
+        }
+    }
+
+    pub fn apply(&self, old: u64, new: u64) -> u64 {
+        match self {
+            Merge::Add => old + new,
+            Merge::Max => old.max(new),
+            Merge::Replace => new,
+        }
+    }
+}
+
+// Time spent in an application from a unix timestamp, as recorded by another tracker
+pub struct Interval {
+    pub app: String,
+    pub start: i64,
+    pub seconds: u64,
+    // The application owned the focused window during the interval
+    pub foreground: bool,
+}
+
+// Data read from a file, either as days and hours or as intervals to split into them
+#[derive(Default)]
+pub struct Import {
+    pub usage: Vec<UsageRecord>,
+    pub hourly: Vec<HourlyRecord>,
+    pub intervals: Vec<Interval>,
+}
+
+#[derive(Default)]
+pub struct ImportSummary {
+    pub dry_run: bool,
+    pub new_apps: Vec<String>,
+    pub days_created: usize,
+    pub days_updated: usize,
+    pub hours_created: usize,
+    pub hours_updated: usize,
+    pub sec_added: u64,
+    pub sec_removed: u64,
+    // Days older than the storage, already rolled up into their month
+    pub old_days: BTreeSet<NaiveDate>,
+}
+
+impl std::fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut output = String::new();
+        if self.dry_run {
+            output.push_str("\tImport summary (dry run, nothing was changed) :\n");
+        } else {
+            output.push_str("\tImport summary :\n");
+        }
+        output.push_str(&format!("Applications created : {}", self.new_apps.len()));
+        if !self.new_apps.is_empty() {
+            output.push_str(&format!(" ({})", self.new_apps.join(", ")));
+        }
+        output.push('\n');
+        output.push_str(&format!("Days created : {}\n", self.days_created));
+        output.push_str(&format!("Days updated : {}\n", self.days_updated));
+        output.push_str(&format!("Hours created : {}\n", self.hours_created));
+        output.push_str(&format!("Hours updated : {}\n", self.hours_updated));
+        output.push_str(&format!("Time added : {}\n", Time::new(self.sec_added)));
+        output.push_str(&format!("Time removed : {}", Time::new(self.sec_removed)));
+        if let (Some(first), Some(last)) = (self.old_days.first(), self.old_days.last()) {
+            output.push_str(&format!(
+                "\nDays skipped, older than the storage : {} (from {} to {})",
+                self.old_days.len(),
+                first,
+                last
+            ));
+        }
+
+        write!(f, "{}", output)
+    }
+}
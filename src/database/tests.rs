@@ -0,0 +1,775 @@
+use super::*;
+
+// Names that broke the queries when they were built with format!
+const HOSTILE_NAMES: [&str; 10] = [
+    "it's",
+    "\"quoted\"",
+    "[bracket]",
+    "close]",
+    "x'); DROP TABLE apps; --",
+    "Robert'); DELETE FROM notification; --",
+    "date",
+    "100%_like",
+    "日本語 🎮",
+    "a;b",
+];
+
+fn database() -> Database {
+    Database::in_memory().unwrap()
+}
+
+fn add_apps(database: &Database) {
+    for name in HOSTILE_NAMES {
+        database.add_app(name.to_string()).unwrap();
+    }
+}
+
+// Records the seconds as if the application had been running during an update
+fn record(database: &Database, name: &str, seconds: u64) {
+    let tracked = Tracked {
+        apps: vec![TrackedApp {
+            name: name.to_string(),
+            matchers: Vec::new(),
+        }],
+        groups: Vec::new(),
+    };
+    let mut increments = Increments::new(&tracked);
+    increments.apps[0].running = seconds;
+    database.add_time(&tracked, &increments).unwrap();
+}
+
+#[test]
+fn hostile_names_are_added_and_deleted() {
+    let database = database();
+    add_apps(&database);
+
+    let tracked = database.get_tracked().unwrap();
+    for name in HOSTILE_NAMES {
+        assert!(database.contain_names(name).unwrap(), "{}", name);
+        assert!(tracked.apps.iter().any(|app| app.name == name), "{}", name);
+    }
+
+    for name in HOSTILE_NAMES {
+        database.del_app(name.to_string()).unwrap();
+        assert!(!database.contain_names(name).unwrap(), "{}", name);
+    }
+    assert!(database.contain_names(SCREENTIME).unwrap());
+}
+
+#[test]
+fn hostile_names_are_queried() {
+    let database = database();
+    add_apps(&database);
+    let today = database.today().unwrap();
+
+    for (i, name) in HOSTILE_NAMES.iter().enumerate() {
+        record(&database, name, 60 * (i as u64 + 1));
+    }
+
+    let day = database.get_time_day(today).unwrap();
+    for (i, name) in HOSTILE_NAMES.iter().enumerate() {
+        let expected = 60 * (i as u64 + 1);
+        let value = day.iter().find(|v| v.name == *name).unwrap();
+        assert_eq!(value.sec_total, expected, "{}", name);
+
+        let values = database.get_time_app(name, today, 1).unwrap();
+        assert_eq!(values.len(), 1, "{}", name);
+        assert_eq!(values[0].name, *name);
+        assert_eq!(values[0].sec_total, expected, "{}", name);
+    }
+
+    // The history is kept once the application is no longer followed
+    database.del_app(HOSTILE_NAMES[4].to_string()).unwrap();
+    let values = database.get_time_app(HOSTILE_NAMES[4], today, 1).unwrap();
+    assert_eq!(values[0].sec_total, 300);
+}
+
+// Logs the notification of the daily limit of the screen time with these minutes
+fn log(database: &Database, date: NaiveDate, minutes: u16, kind: NotifKind, reminder: u16) {
+    let id = database
+        .conn
+        .query_row(
+            "SELECT id FROM notification WHERE app = ?1 AND time = ?2 AND period = 'day'",
+            (SCREENTIME, minutes),
+            |row| row.get(0),
+        )
+        .unwrap();
+    database.log_notification(id, date, kind, reminder).unwrap();
+}
+
+fn due(database: &Database, names: &[String], values: &[u64], date: NaiveDate) -> Vec<usize> {
+    database
+        .due_notifications(names, values, date)
+        .unwrap()
+        .into_iter()
+        .map(|due| due.index)
+        .collect()
+}
+
+#[test]
+fn hostile_names_have_notifications() {
+    let database = database();
+    add_apps(&database);
+    let today = database.today().unwrap();
+    let names: Vec<String> = HOSTILE_NAMES.iter().map(|name| name.to_string()).collect();
+    let values = vec![60; names.len()];
+
+    database
+        .add_notif(&Notif::new(HOSTILE_NAMES[0], 1))
+        .unwrap();
+    database
+        .add_notif(&Notif::new(HOSTILE_NAMES[7], 1))
+        .unwrap();
+    assert_eq!(due(&database, &names, &values, today), vec![0, 7]);
+
+    for name in HOSTILE_NAMES {
+        database.add_notif(&Notif::new(name, 1)).unwrap();
+    }
+    assert_eq!(
+        due(&database, &names, &values, today),
+        (0..names.len()).collect::<Vec<_>>()
+    );
+
+    // A quote in a name cannot widen the query to the limits of the other applications
+    let other = vec![String::from("nobody' OR '1' = '1")];
+    assert!(due(&database, &other, &[60], today).is_empty());
+
+    for name in HOSTILE_NAMES {
+        database.del_notif(name, None).unwrap();
+    }
+    assert!(due(&database, &names, &values, today).is_empty());
+}
+
+#[test]
+fn notifications_are_sent_once_a_day() {
+    let database = database();
+    let today = database.today().unwrap();
+    let names = vec![SCREENTIME.to_string()];
+    database.add_notif(&Notif::new(SCREENTIME, 10)).unwrap();
+
+    assert!(due(&database, &names, &[599], today).is_empty());
+    assert_eq!(due(&database, &names, &[600], today), vec![0]);
+    // Missed updates do not prevent the notification
+    assert_eq!(due(&database, &names, &[6000], today), vec![0]);
+
+    log(&database, today, 10, NotifKind::Limit, 0);
+    assert!(due(&database, &names, &[6000], today).is_empty());
+    assert_eq!(
+        due(&database, &names, &[6000], today + Duration::days(1)),
+        vec![0]
+    );
+
+    // A limit lowered below the time already spent is notified at the next update
+    database.del_notif(SCREENTIME, Some(10)).unwrap();
+    database.add_notif(&Notif::new(SCREENTIME, 5)).unwrap();
+    assert_eq!(due(&database, &names, &[6000], today), vec![0]);
+}
+
+#[test]
+fn hostile_names_are_grouped() {
+    let database = database();
+    add_apps(&database);
+
+    let group = "group'; DROP TABLE groups; --";
+    database.add_group(group, &HOSTILE_NAMES).unwrap();
+    assert!(database.contain_group(group).unwrap());
+
+    let tracked = database.get_tracked().unwrap();
+    let members = &tracked
+        .groups
+        .iter()
+        .find(|g| g.name == group)
+        .unwrap()
+        .members;
+    assert_eq!(members.len(), HOSTILE_NAMES.len());
+
+    database.del_group(group, &[]).unwrap();
+    assert!(!database.contain_group(group).unwrap());
+}
+
+#[test]
+fn deleted_groups_leave_no_monthly_time() {
+    let database = database();
+    let date = NaiveDate::from_ymd_opt(2020, 1, 15).unwrap();
+    database.add_group("work", &[SCREENTIME]).unwrap();
+    database
+        .conn
+        .execute(
+            "INSERT INTO group_usage_monthly (group_id, month, seconds, foreground_seconds)
+            SELECT id, '2020-01', 3600, 600 FROM groups WHERE name = 'work'",
+            (),
+        )
+        .unwrap();
+    database.del_group("work", &[]).unwrap();
+
+    // The ids of the groups are reused
+    database.add_group("play", &[SCREENTIME]).unwrap();
+    let months = database.get_time_month_groups(date).unwrap();
+    let play = months.iter().find(|v| v.name == "play").unwrap();
+    assert_eq!((play.sec_total, play.foreground_total), (0, 0));
+}
+
+#[test]
+fn empty_names_are_refused() {
+    let database = database();
+
+    for name in ["", "   ", "tab\tname", "line\nbreak"] {
+        assert!(
+            matches!(database.add_app(name.to_string()), Err(Error::Usage(_))),
+            "{:?}",
+            name
+        );
+        assert!(
+            matches!(
+                database.add_group(name, &[SCREENTIME]),
+                Err(Error::Usage(_))
+            ),
+            "{:?}",
+            name
+        );
+        assert!(!database.contain_names(name).unwrap());
+    }
+}
+
+#[test]
+fn reminders_are_sent_past_the_limit() {
+    let database = database();
+    let today = database.today().unwrap();
+    let names = vec![SCREENTIME.to_string()];
+    database
+        .add_notif(&Notif {
+            repeat_minutes: 15,
+            critical_after: Some(1),
+            ..Notif::new(SCREENTIME, 10)
+        })
+        .unwrap();
+
+    let reminder = |seconds: u64| {
+        let due = database
+            .due_notifications(&names, &[seconds], today)
+            .unwrap();
+        due.first().map(|due| (due.reminder, due.urgency))
+    };
+
+    assert_eq!(reminder(600), Some((0, Urgency::Normal)));
+    log(&database, today, 10, NotifKind::Limit, 0);
+    assert_eq!(reminder(1440), None);
+    assert_eq!(reminder(1500), Some((1, Urgency::Normal)));
+    log(&database, today, 10, NotifKind::Limit, 1);
+    assert_eq!(reminder(1500), None);
+
+    // Only the last of the missed reminders is sent, beyond the first one it is critical
+    assert_eq!(reminder(3300), Some((3, Urgency::Critical)));
+    log(&database, today, 10, NotifKind::Limit, 3);
+    assert_eq!(reminder(3400), None);
+
+    // Without a repeat interval, a single notification is sent
+    database.add_notif(&Notif::new(SCREENTIME, 10)).unwrap();
+    assert_eq!(reminder(6000), None);
+}
+
+#[test]
+fn applications_have_several_limits() {
+    let database = database();
+    let today = database.today().unwrap();
+    let names = vec![SCREENTIME.to_string()];
+    database.add_notif(&Notif::new(SCREENTIME, 60)).unwrap();
+    database
+        .add_notif(&Notif {
+            message: Some(String::from("Go outside")),
+            ..Notif::new(SCREENTIME, 120)
+        })
+        .unwrap();
+
+    let thresholds = |seconds: u64| -> Vec<(u16, Option<String>)> {
+        database
+            .due_notifications(&names, &[seconds], today)
+            .unwrap()
+            .into_iter()
+            .map(|due| (due.threshold, due.message))
+            .collect()
+    };
+
+    assert_eq!(thresholds(3600), vec![(60, None)]);
+    log(&database, today, 60, NotifKind::Limit, 0);
+    assert!(thresholds(7199).is_empty());
+    assert_eq!(
+        thresholds(7200),
+        vec![(120, Some(String::from("Go outside")))]
+    );
+
+    // Adding the same threshold replaces it, removing it keeps the other ones
+    database.add_notif(&Notif::new(SCREENTIME, 120)).unwrap();
+    assert_eq!(database.get_notifs().unwrap().len(), 2);
+    assert_eq!(thresholds(7200), vec![(120, None)]);
+    database.del_notif(SCREENTIME, Some(120)).unwrap();
+    assert!(thresholds(7200).is_empty());
+    assert!(matches!(
+        database.del_notif(SCREENTIME, Some(120)),
+        Err(Error::Usage(_))
+    ));
+    assert_eq!(database.get_notifs().unwrap().len(), 1);
+}
+
+#[test]
+fn warnings_are_sent_before_the_limit() {
+    let database = database();
+    let today = database.today().unwrap();
+    let names = vec![SCREENTIME.to_string()];
+    database
+        .add_notif(&Notif {
+            warning_minutes: 10,
+            ..Notif::new(SCREENTIME, 60)
+        })
+        .unwrap();
+
+    let kinds = |seconds: u64| -> Vec<NotifKind> {
+        database
+            .due_notifications(&names, &[seconds], today)
+            .unwrap()
+            .into_iter()
+            .map(|due| due.kind)
+            .collect()
+    };
+
+    assert!(kinds(2999).is_empty());
+    assert_eq!(kinds(3000), vec![NotifKind::Warning]);
+    log(&database, today, 60, NotifKind::Warning, 0);
+    assert!(kinds(3599).is_empty());
+    assert_eq!(kinds(3600), vec![NotifKind::Limit]);
+
+    // The warning is skipped when the limit is reached between two updates
+    assert_eq!(
+        database
+            .due_notifications(&names, &[3600], today + Duration::days(1))
+            .unwrap()
+            .iter()
+            .map(|due| due.kind)
+            .collect::<Vec<_>>(),
+        vec![NotifKind::Limit]
+    );
+
+    // The warning must come before the limit
+    let invalid = Notif {
+        warning_minutes: 60,
+        ..Notif::new(SCREENTIME, 60)
+    };
+    assert!(matches!(database.add_notif(&invalid), Err(Error::Usage(_))));
+}
+
+#[test]
+fn limits_apply_on_their_days() {
+    let database = database();
+    let today = database.today().unwrap();
+    let monday = Period::Week.start(today);
+    let saturday = monday + Duration::days(5);
+    let names = vec![SCREENTIME.to_string()];
+    let limit = |minutes: u16, days: &str| Notif {
+        days: Days::parse(days).unwrap(),
+        ..Notif::new(SCREENTIME, minutes)
+    };
+    database.add_notif(&limit(60, "weekdays")).unwrap();
+    database.add_notif(&limit(180, "weekends")).unwrap();
+
+    assert_eq!(due(&database, &names, &[3600], monday), vec![0]);
+    assert!(due(&database, &names, &[3600], saturday).is_empty());
+    assert_eq!(due(&database, &names, &[10800], saturday), vec![0]);
+
+    assert_eq!(Days::parse("sat,sunday"), Days::parse("weekends"));
+    assert_eq!(Days::parse("mon,wed").unwrap().to_string(), "mon,wed");
+    assert!(Days::parse("someday").is_none());
+    assert!(matches!(
+        database.add_notif(&Notif {
+            period: Period::Week,
+            ..limit(60, "mon")
+        }),
+        Err(Error::Usage(_))
+    ));
+}
+
+#[test]
+fn weekly_limits_count_the_week() {
+    let database = database();
+    let today = database.today().unwrap();
+    // A whole week of the past, so that the days before the date have usage
+    let monday = Period::Week.start(today) - Duration::days(7);
+    let thursday = monday + Duration::days(3);
+    let names = vec![SCREENTIME.to_string()];
+    for (day, seconds) in [
+        (monday - Duration::days(1), 36000),
+        (monday, 3600),
+        (monday + Duration::days(2), 3600),
+    ] {
+        database
+            .conn
+            .execute(
+                "INSERT INTO usage (app_id, date, seconds) SELECT id, ?2, ?3 FROM apps WHERE name = ?1",
+                (SCREENTIME, day.to_string(), seconds),
+            )
+            .unwrap();
+    }
+    let weekly = Notif {
+        period: Period::Week,
+        warning_minutes: 30,
+        ..Notif::new(SCREENTIME, 180)
+    };
+    // The days of the week must still be kept day by day
+    database.change_settings("storage_size", "6").unwrap();
+    assert!(database.add_notif(&weekly).is_err());
+    database.change_settings("storage_size", "30").unwrap();
+    database.add_notif(&weekly).unwrap();
+
+    let kinds = |seconds: u64| -> Vec<(NotifKind, u64)> {
+        database
+            .due_notifications(&names, &[seconds], thursday)
+            .unwrap()
+            .into_iter()
+            .map(|due| (due.kind, due.used))
+            .collect()
+    };
+
+    // The Sunday before belongs to the previous week
+    assert!(kinds(1799).is_empty());
+    assert_eq!(kinds(1800), vec![(NotifKind::Warning, 9000)]);
+    assert_eq!(kinds(3600), vec![(NotifKind::Limit, 10800)]);
+
+    // A weekly limit is logged once for its week
+    let id = database
+        .conn
+        .query_row("SELECT id FROM notification", [], |row| row.get(0))
+        .unwrap();
+    database
+        .log_notification(id, monday, NotifKind::Limit, 0)
+        .unwrap();
+    assert!(kinds(3600).is_empty());
+    assert_eq!(
+        database
+            .due_notifications(&names, &[10800], monday + Duration::days(7))
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn actions_wait_for_the_grace_period() {
+    let database = database();
+    let today = database.today().unwrap();
+    let name = "game";
+    database.add_app(name.to_string()).unwrap();
+    database
+        .add_notif(&Notif {
+            action: Some(Action::Kill),
+            ..Notif::new(name, 60)
+        })
+        .unwrap();
+    let names = vec![name.to_string()];
+
+    let steps = |running: bool, seconds: u64, now: i64| -> Vec<bool> {
+        database
+            .due_enforcements(&names, &[running], &[seconds], today, now)
+            .unwrap()
+            .into_iter()
+            .map(|due| due.grace)
+            .collect()
+    };
+
+    let now = Utc::now().timestamp();
+    assert!(steps(true, 3599, now).is_empty());
+    assert!(steps(false, 3600, now).is_empty());
+    assert_eq!(steps(true, 3600, now), vec![true]);
+
+    let id = database
+        .conn
+        .query_row("SELECT id FROM notification", [], |row| row.get(0))
+        .unwrap();
+    database
+        .log_notification(id, today, NotifKind::Grace, 0)
+        .unwrap();
+    let grace = DEFAULT_GRACE_MINUTES as i64 * 60;
+    assert!(steps(true, 3660, now + grace - 60).is_empty());
+    // The action is taken at each update past the grace period
+    assert_eq!(steps(true, 3660, now + grace + 1), vec![false]);
+    assert_eq!(steps(true, 3720, now + grace + 61), vec![false]);
+
+    // A snooze suspends the action for the day
+    assert!(matches!(database.snooze(name, "  "), Err(Error::Usage(_))));
+    database.snooze(name, "finishing a match").unwrap();
+    assert!(steps(true, 3720, now + grace + 61).is_empty());
+    assert_eq!(
+        database.get_budgets().unwrap()[0].snoozed.as_deref(),
+        Some("finishing a match")
+    );
+}
+
+#[test]
+fn actions_are_checked() {
+    let database = database();
+    database.add_app(String::from("game")).unwrap();
+
+    let invalid = [
+        Notif {
+            action: Some(Action::Kill),
+            ..Notif::new(SCREENTIME, 60)
+        },
+        Notif {
+            action: Some(Action::Command),
+            ..Notif::new("game", 60)
+        },
+        Notif {
+            command: Some(String::from("true")),
+            ..Notif::new("game", 60)
+        },
+    ];
+    for notif in &invalid {
+        assert!(
+            matches!(database.add_notif(notif), Err(Error::Usage(_))),
+            "{}",
+            notif
+        );
+    }
+
+    // Without a grace period, the action follows its warning
+    database
+        .add_notif(&Notif {
+            action: Some(Action::Command),
+            command: Some(String::from("loginctl lock-session")),
+            grace_minutes: 0,
+            ..Notif::new(SCREENTIME, 60)
+        })
+        .unwrap();
+    let due = database
+        .due_enforcements(
+            &[SCREENTIME.to_string()],
+            &[true],
+            &[3600],
+            database.today().unwrap(),
+            Utc::now().timestamp(),
+        )
+        .unwrap();
+    assert_eq!(
+        due.iter().map(|due| due.grace).collect::<Vec<_>>(),
+        vec![true, false]
+    );
+}
+
+#[test]
+fn stats_leave_out_the_rolled_up_months() {
+    let database = database();
+    let today = database.today().unwrap();
+    database.change_settings("storage_size", "5").unwrap();
+    for (days, seconds) in [(40, 36000), (20, 7200), (2, 1200), (1, 600)] {
+        database
+            .conn
+            .execute(
+                "INSERT INTO usage (app_id, date, seconds)
+                SELECT id, ?2, ?3 FROM apps WHERE name = ?1",
+                (
+                    SCREENTIME,
+                    (today - Duration::days(days)).to_string(),
+                    seconds,
+                ),
+            )
+            .unwrap();
+    }
+    database.apply_retention().unwrap();
+
+    let values = database.get_time_app(SCREENTIME, today, 30).unwrap();
+    assert!(values.iter().any(|v| v.month));
+    assert_eq!(values.iter().filter(|v| !v.month).count(), 6);
+
+    let stat = Stat::new(&values);
+    assert_eq!(stat.max.total_seconds(), 1200);
+    assert_eq!(stat.min.total_seconds(), 0);
+    assert_eq!(stat.sum.total_seconds(), 1800);
+    assert_eq!(stat.mean.total_seconds(), 300);
+}
+
+#[test]
+fn exports_keep_the_rolled_up_months() {
+    let database = database();
+    let today = database.today().unwrap();
+    database.change_settings("storage_size", "5").unwrap();
+    for (days, seconds) in [(40, 36000), (20, 7200), (1, 600)] {
+        database
+            .conn
+            .execute(
+                "INSERT INTO usage (app_id, date, seconds)
+                SELECT id, ?2, ?3 FROM apps WHERE name = ?1",
+                (
+                    SCREENTIME,
+                    (today - Duration::days(days)).to_string(),
+                    seconds,
+                ),
+            )
+            .unwrap();
+    }
+    database.apply_retention().unwrap();
+
+    let records = database.get_records(None, today).unwrap();
+    assert_eq!(
+        records.usage.iter().map(|r| r.seconds).collect::<Vec<_>>(),
+        vec![600]
+    );
+    assert_eq!(
+        records.monthly.iter().map(|r| r.seconds).sum::<u64>(),
+        43200
+    );
+    assert!(records.monthly.iter().all(|r| r.date.day() == 1));
+}
+
+#[test]
+fn invalid_dates_are_reported() {
+    let database = database();
+    database
+        .conn
+        .execute(
+            "INSERT INTO usage (app_id, date, seconds)
+            SELECT id, '2025-03-10 ', 600 FROM apps WHERE name = ?1",
+            [SCREENTIME],
+        )
+        .unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+    assert!(database.get_time_app(SCREENTIME, date, 10).is_err());
+}
+
+#[test]
+fn baseline_databases_are_migrated() {
+    // The schema before the migrations, with one column of minutes per application
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE time (date DATE PRIMARY KEY, pc INTEGER DEFAULT 0);
+        ALTER TABLE time ADD COLUMN [firefox] INTEGER DEFAULT 0;
+        ALTER TABLE time ADD COLUMN [it's] INTEGER DEFAULT 0;
+        CREATE TABLE settings (attribute TEXT PRIMARY KEY, value TEXT);
+        CREATE TABLE notification (app TEXT PRIMARY KEY, time INTEGER);
+        INSERT INTO time VALUES ('2024-05-01', 120, 45, 0);
+        INSERT INTO time VALUES ('2024-05-02', 60, 0, 10);
+        INSERT INTO settings VALUES ('storage_size', '3650');
+        INSERT INTO notification VALUES ('pc', 90);
+        INSERT INTO notification VALUES ('firefox', 30);",
+    )
+    .unwrap();
+
+    let database = Database::open(conn).unwrap();
+    let version: usize = database
+        .conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .unwrap();
+    assert_eq!(version, migration::SCHEMA_VERSION);
+
+    let apps: Vec<(String, bool)> = database
+        .conn
+        .prepare("SELECT name, tracked FROM apps ORDER BY id")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap();
+    assert_eq!(
+        apps,
+        vec![
+            (SCREENTIME.to_string(), true),
+            ("firefox".to_string(), true),
+            ("it's".to_string(), true),
+        ]
+    );
+
+    let usage: Vec<(String, String, u64, u64)> = database
+        .conn
+        .prepare(
+            "SELECT a.name, u.date, u.seconds, u.foreground_seconds FROM usage u
+            JOIN apps a ON a.id = u.app_id ORDER BY u.date, a.id",
+        )
+        .unwrap()
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap();
+    assert_eq!(
+        usage,
+        vec![
+            (SCREENTIME.to_string(), "2024-05-01".to_string(), 7200, 0),
+            ("firefox".to_string(), "2024-05-01".to_string(), 2700, 0),
+            (SCREENTIME.to_string(), "2024-05-02".to_string(), 3600, 0),
+            ("it's".to_string(), "2024-05-02".to_string(), 600, 0),
+        ]
+    );
+
+    let notifs = database.get_notifs().unwrap();
+    assert_eq!(
+        notifs
+            .iter()
+            .map(|n| (n.app.as_str(), n.minutes, n.period, n.days, n.action))
+            .collect::<Vec<_>>(),
+        vec![
+            ("firefox", 30, Period::Day, Days::ALL, None),
+            (SCREENTIME, 90, Period::Day, Days::ALL, None),
+        ]
+    );
+    assert_eq!(database.get_settings().unwrap().storage_size, 3650);
+}
+
+#[test]
+fn imports_leave_the_rolled_up_days() {
+    let database = database();
+    let today = database.today().unwrap();
+    database.change_settings("storage_size", "5").unwrap();
+    let old = today - Duration::days(20);
+    let import = Import {
+        usage: vec![
+            UsageRecord {
+                date: old,
+                app: SCREENTIME.to_string(),
+                minutes: 60,
+                seconds: 3600,
+                foreground_seconds: 0,
+            },
+            UsageRecord {
+                date: today,
+                app: SCREENTIME.to_string(),
+                minutes: 10,
+                seconds: 600,
+                foreground_seconds: 0,
+            },
+        ],
+        ..Import::default()
+    };
+
+    let total = |database: &Database| {
+        database
+            .get_time_app(SCREENTIME, today, 30)
+            .unwrap()
+            .iter()
+            .map(|v| v.sec_total)
+            .sum::<u64>()
+    };
+    for _ in 0..2 {
+        let summary = database.import(&import, Merge::Max, false).unwrap();
+        assert_eq!(summary.old_days.into_iter().collect::<Vec<_>>(), vec![old]);
+        database.apply_retention().unwrap();
+        assert_eq!(total(&database), 600);
+    }
+}
+
+#[test]
+fn empty_matchers_are_refused() {
+    let database = database();
+    database.add_app("foo".to_string()).unwrap();
+
+    for (kind, pattern) in [
+        ("cmdline", ""),
+        ("comm", ""),
+        ("comm", "  "),
+        ("name", "\t"),
+    ] {
+        assert!(matches!(
+            database.add_matcher("foo", kind, pattern),
+            Err(Error::Usage(_))
+        ));
+    }
+    database.add_matcher("foo", "comm", "^foo$").unwrap();
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Serialize, Serializer};
+
+// Time zone in which the days are counted, the one of the system unless another one
+// is given in the settings
+#[derive(Clone, Copy)]
+pub enum Zone {
+    Local,
+    Named(Tz),
+}
+
+impl Zone {
+    // Either "local" or the name of a zone of the tz database, like "Europe/Paris"
+    pub fn parse(value: &str) -> Option<Zone> {
+        if value == "local" {
+            return Some(Zone::Local);
+        }
+
+        value.parse::<Tz>().ok().map(Zone::Named)
+    }
+
+    pub fn now(&self) -> NaiveDateTime {
+        match self {
+            Zone::Local => Local::now().naive_local(),
+            Zone::Named(tz) => Utc::now().with_timezone(tz).naive_local(),
+        }
+    }
+
+    pub fn datetime(&self, timestamp: i64) -> Option<NaiveDateTime> {
+        let datetime = DateTime::from_timestamp(timestamp, 0)?;
+        match self {
+            Zone::Local => Some(datetime.with_timezone(&Local).naive_local()),
+            Zone::Named(tz) => Some(datetime.with_timezone(tz).naive_local()),
+        }
+    }
+
+    // A time skipped by a change of offset has no timestamp, a repeated one takes the first
+    pub fn timestamp(&self, datetime: NaiveDateTime) -> Option<i64> {
+        match self {
+            Zone::Local => Local
+                .from_local_datetime(&datetime)
+                .earliest()
+                .map(|d| d.timestamp()),
+            Zone::Named(tz) => tz
+                .from_local_datetime(&datetime)
+                .earliest()
+                .map(|d| d.timestamp()),
+        }
+    }
+}
+
+impl Serialize for Zone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Zone::Local => write!(f, "local"),
+            Zone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+// Day to which a time belongs when the days start at day_start instead of midnight
+pub fn day_of(datetime: NaiveDateTime, day_start: NaiveTime) -> NaiveDate {
+    (datetime - (day_start - NaiveTime::MIN)).date()
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+use std::io;
+
+// Failure of a command, it is printed before the process exits with a non-zero code
+#[derive(Debug)]
+pub enum Error {
+    Database(rusqlite::Error),
+    // The running processes could not be read
+    Probe(io::Error),
+    Notification(notify_rust::error::Error),
+    // The processes of an application could not be terminated or the command of a limit
+    // could not be run
+    Enforcement(io::Error),
+    // A date or a value read from the database or from the arguments is not valid
+    Parse(String),
+    // A setting stored in the database is not valid
+    Config(String),
+    Io(io::Error),
+    // A file that could not be exported or imported
+    File(String, io::Error),
+    // The arguments or the names given by the user are not valid
+    Usage(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn usage(message: impl Into<String>) -> Error {
+        Error::Usage(message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Database(e) => write!(f, "unable to work with database : {}", e),
+            Error::Probe(e) => write!(f, "unable to read the running processes : {}", e),
+            Error::Notification(e) => write!(f, "unable to send the notification : {}", e),
+            Error::Enforcement(e) => write!(f, "unable to enforce the limit : {}", e),
+            Error::Parse(message) => write!(f, "{}", message),
+            Error::Config(message) => write!(f, "invalid setting : {}", message),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::File(path, e) => write!(f, "unable to use the file {} : {}", path, e),
+            Error::Usage(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Error {
+        Error::Database(e)
+    }
+}
+
+impl From<notify_rust::error::Error> for Error {
+    fn from(e: notify_rust::error::Error) -> Error {
+        Error::Notification(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
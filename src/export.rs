@@ -0,0 +1,131 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::str::FromStr;
+
+use crate::database::{HourlyRecord, MonthlyRecord, Records, SessionRecord, UsageRecord};
+
+pub enum Format {
+    Csv,
+    Json,
+    Ndjson,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Format, ()> {
+        match value {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            _ => Err(()),
+        }
+    }
+}
+
+// One line of the newline delimited JSON, tagged with the kind of the record
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Line<'a> {
+    Usage(&'a UsageRecord),
+    Hourly(&'a HourlyRecord),
+    Session(&'a SessionRecord),
+    Monthly(&'a MonthlyRecord),
+}
+
+// The CSV holds the four kinds of record in a single table, the columns which do not
+// apply to a kind are left empty
+#[derive(Serialize, Default)]
+struct Row<'a> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    date: Option<NaiveDate>,
+    app: &'a str,
+    hour: Option<u32>,
+    minutes: Option<u64>,
+    seconds: u64,
+    foreground_seconds: Option<u64>,
+    started_at: Option<NaiveDateTime>,
+    ended_at: Option<NaiveDateTime>,
+}
+
+// Writes the records to the file, or to the standard output if the path is -
+pub fn export(records: &Records, format: Format, path: &str) -> io::Result<()> {
+    if path == "-" {
+        return write_records(records, format, io::stdout().lock());
+    }
+
+    write_records(records, format, BufWriter::new(File::create(path)?))
+}
+
+fn write_records(records: &Records, format: Format, mut writer: impl Write) -> io::Result<()> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut writer, records)?;
+            writeln!(writer)?;
+        }
+        Format::Ndjson => {
+            let lines = records
+                .usage
+                .iter()
+                .map(Line::Usage)
+                .chain(records.hourly.iter().map(Line::Hourly))
+                .chain(records.sessions.iter().map(Line::Session))
+                .chain(records.monthly.iter().map(Line::Monthly));
+            for line in lines {
+                serde_json::to_writer(&mut writer, &line)?;
+                writeln!(writer)?;
+            }
+        }
+        Format::Csv => {
+            let mut csv = csv::Writer::from_writer(&mut writer);
+            for record in &records.usage {
+                csv.serialize(Row {
+                    kind: "usage",
+                    date: Some(record.date),
+                    app: &record.app,
+                    minutes: Some(record.minutes),
+                    seconds: record.seconds,
+                    foreground_seconds: Some(record.foreground_seconds),
+                    ..Row::default()
+                })?;
+            }
+            for record in &records.hourly {
+                csv.serialize(Row {
+                    kind: "hourly",
+                    date: Some(record.date),
+                    app: &record.app,
+                    hour: Some(record.hour),
+                    seconds: record.seconds,
+                    ..Row::default()
+                })?;
+            }
+            for record in &records.sessions {
+                csv.serialize(Row {
+                    kind: "session",
+                    date: Some(record.date),
+                    app: &record.app,
+                    seconds: record.seconds,
+                    started_at: Some(record.started_at),
+                    ended_at: Some(record.ended_at),
+                    ..Row::default()
+                })?;
+            }
+            for record in &records.monthly {
+                csv.serialize(Row {
+                    kind: "monthly",
+                    date: Some(record.date),
+                    app: &record.app,
+                    seconds: record.seconds,
+                    foreground_seconds: Some(record.foreground_seconds),
+                    ..Row::default()
+                })?;
+            }
+            csv.flush()?;
+        }
+    }
+
+    writer.flush()
+}
@@ -0,0 +1,266 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Read};
+use std::str::FromStr;
+
+use crate::database::{HourlyRecord, Import, Interval, SCREENTIME, UsageRecord, Zone};
+
+const NUMBER_SECONDS_IN_MINUTE: u64 = 60;
+// arbtt samples every minute unless told otherwise, its human dump does not say
+const ARBTT_DEFAULT_RATE: u64 = 60;
+
+pub enum Source {
+    Csv,
+    Json,
+    Ndjson,
+    ActivityWatch,
+    Arbtt,
+}
+
+impl FromStr for Source {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Source, ()> {
+        match value {
+            "csv" => Ok(Source::Csv),
+            "json" => Ok(Source::Json),
+            "ndjson" => Ok(Source::Ndjson),
+            "activitywatch" => Ok(Source::ActivityWatch),
+            "arbtt" => Ok(Source::Arbtt),
+            _ => Err(()),
+        }
+    }
+}
+
+// A row of an export, the time is given in seconds or in minutes. The rows of the
+// sessions and of the months are ignored.
+#[derive(Deserialize)]
+struct Row {
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    date: NaiveDate,
+    app: String,
+    #[serde(default)]
+    hour: Option<u32>,
+    #[serde(default)]
+    minutes: Option<u64>,
+    #[serde(default)]
+    seconds: Option<u64>,
+    #[serde(default)]
+    foreground_seconds: Option<u64>,
+}
+
+// Either the object written by the JSON export or a plain list of rows
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonFile {
+    Export {
+        usage: Vec<Row>,
+        #[serde(default)]
+        hourly: Vec<Row>,
+    },
+    Rows(Vec<Row>),
+}
+
+// Reads the file, or the standard input if the path is -
+pub fn import(path: &str, source: Source, zone: Zone) -> io::Result<Import> {
+    let mut content = String::new();
+    if path == "-" {
+        io::stdin().read_to_string(&mut content)?;
+    } else {
+        content = fs::read_to_string(path)?;
+    }
+
+    match source {
+        Source::Csv => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let rows = reader.deserialize().collect::<Result<Vec<Row>, _>>()?;
+            Ok(from_rows(rows))
+        }
+        Source::Json => match serde_json::from_str(&content)? {
+            JsonFile::Export { mut usage, hourly } => {
+                usage.extend(hourly.into_iter().map(|row| Row {
+                    kind: Some(String::from("hourly")),
+                    ..row
+                }));
+                Ok(from_rows(usage))
+            }
+            JsonFile::Rows(rows) => Ok(from_rows(rows)),
+        },
+        Source::Ndjson => {
+            let mut rows = Vec::new();
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                let value: Value = serde_json::from_str(line)?;
+                if value["type"] != "session" && value["type"] != "monthly" {
+                    rows.push(serde_json::from_value(value)?);
+                }
+            }
+            Ok(from_rows(rows))
+        }
+        Source::ActivityWatch => activitywatch(&content),
+        Source::Arbtt => arbtt(&content, zone),
+    }
+}
+
+fn from_rows(rows: Vec<Row>) -> Import {
+    let mut import = Import::default();
+    for row in rows {
+        let seconds = row
+            .seconds
+            .or(row
+                .minutes
+                .map(|minutes| minutes * NUMBER_SECONDS_IN_MINUTE))
+            .unwrap_or(0);
+
+        match (row.kind.as_deref(), row.hour) {
+            (Some("hourly"), Some(hour)) => import.hourly.push(HourlyRecord {
+                date: row.date,
+                app: row.app,
+                hour,
+                seconds,
+            }),
+            (None | Some("usage"), _) => import.usage.push(UsageRecord {
+                date: row.date,
+                app: row.app,
+                minutes: seconds / NUMBER_SECONDS_IN_MINUTE,
+                seconds,
+                foreground_seconds: row.foreground_seconds.unwrap_or(0),
+            }),
+            _ => (),
+        }
+    }
+
+    import
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// Export of the buckets of ActivityWatch. The focused windows give the time of the
+// applications, and the time during which the user was not away from the keyboard
+// gives the screen time.
+fn activitywatch(content: &str) -> io::Result<Import> {
+    let export: Value = serde_json::from_str(content)?;
+    let buckets = export["buckets"]
+        .as_object()
+        .ok_or_else(|| invalid("no buckets in the ActivityWatch export"))?;
+
+    let mut import = Import::default();
+    for bucket in buckets.values() {
+        let kind = bucket["type"].as_str().unwrap_or_default();
+        for event in bucket["events"].as_array().into_iter().flatten() {
+            let app = match kind {
+                "currentwindow" => event["data"]["app"].as_str(),
+                "afkstatus" if event["data"]["status"] == "not-afk" => Some(SCREENTIME),
+                _ => None,
+            };
+            let start = event["timestamp"]
+                .as_str()
+                .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok());
+            let seconds = event["duration"].as_f64().unwrap_or(0.0).round() as u64;
+
+            if let (Some(app), Some(start)) = (app, start)
+                && seconds > 0
+            {
+                import.intervals.push(Interval {
+                    app: app.to_string(),
+                    start: start.timestamp(),
+                    seconds,
+                    foreground: kind == "currentwindow",
+                });
+            }
+        }
+    }
+
+    Ok(import)
+}
+
+// Samples dumped by arbtt-dump, in its JSON format or in its human readable one. Every
+// sample counts for the screen time and for each program having a window. The human
+// dump gives local times, they are read in the time zone of the settings.
+fn arbtt(content: &str, zone: Zone) -> io::Result<Import> {
+    let mut import = Import::default();
+
+    if content.trim_start().starts_with('[') {
+        let samples: Vec<Value> = serde_json::from_str(content)?;
+        for sample in samples {
+            let Some(start) = sample["date"]
+                .as_str()
+                .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            else {
+                continue;
+            };
+            let seconds = sample["rate"]
+                .as_u64()
+                .map_or(ARBTT_DEFAULT_RATE, |rate| rate / 1000);
+            let windows = sample["windows"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|w| Some((w["program"].as_str()?.to_string(), w["active"] == true)));
+            push_sample(&mut import, start.timestamp(), seconds, windows);
+        }
+        return Ok(import);
+    }
+
+    // 2025-03-04 09:12:03 (1234ms inactive):
+    //     (*) Alacritty:     nvim
+    let header = Regex::new(r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})").expect("valid regex");
+    let window = Regex::new(r"^\s+\(([* ])\) ([^:]+):").expect("valid regex");
+
+    let mut sample: Option<(i64, Vec<(String, bool)>)> = None;
+    for line in content.lines() {
+        if let Some(captures) = header.captures(line) {
+            if let Some((start, windows)) = sample.take() {
+                push_sample(&mut import, start, ARBTT_DEFAULT_RATE, windows.into_iter());
+            }
+            let start = NaiveDateTime::parse_from_str(&captures[1], "%Y-%m-%d %H:%M:%S")
+                .map_err(|_| invalid("invalid date in the arbtt dump"))?;
+            // A time skipped by a change of offset cannot be placed, its sample is left out
+            sample = zone.timestamp(start).map(|start| (start, Vec::new()));
+        } else if let (Some(captures), Some((_, windows))) = (window.captures(line), &mut sample) {
+            windows.push((captures[2].trim().to_string(), &captures[1] == "*"));
+        }
+    }
+    if let Some((start, windows)) = sample {
+        push_sample(&mut import, start, ARBTT_DEFAULT_RATE, windows.into_iter());
+    }
+
+    Ok(import)
+}
+
+fn push_sample(
+    import: &mut Import,
+    start: i64,
+    seconds: u64,
+    windows: impl Iterator<Item = (String, bool)>,
+) {
+    import.intervals.push(Interval {
+        app: SCREENTIME.to_string(),
+        start,
+        seconds,
+        foreground: false,
+    });
+
+    // A program with several windows is counted once, in the foreground if one of them is
+    let mut programs: Vec<(String, bool)> = Vec::new();
+    for (program, active) in windows {
+        match programs.iter_mut().find(|(p, _)| *p == program) {
+            Some((_, foreground)) => *foreground |= active,
+            None => programs.push((program, active)),
+        }
+    }
+
+    for (app, foreground) in programs {
+        import.intervals.push(Interval {
+            app,
+            start,
+            seconds,
+            foreground,
+        });
+    }
+}
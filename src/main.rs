@@ -1,15 +1,24 @@
-use argh::FromArgs;
-use chrono::{Datelike, Duration, NaiveDate, Utc, Weekday};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Timelike, Weekday};
 use ratatui::style::Color;
-use std::io;
+use std::process::ExitCode;
 use std::str::FromStr;
 
+mod cli;
+mod daemon;
 mod database;
+mod error;
+mod export;
+mod import;
 mod tui;
 
-use database::Database;
+use cli::*;
+use database::{Action, Database, Days, Merge, NUMBER_DAYS_IN_WEEK, Notif, Output, Period, Zone};
+use error::{Error, Result};
+use export::Format;
+use import::Source;
 
 const VERSION_NUMBER: &str = "v0.2.0";
+const DEFAULT_DAEMON_INTERVAL: u16 = 10;
 
 #[derive(PartialEq)]
 enum TypeRequest {
@@ -17,197 +26,339 @@ enum TypeRequest {
     App,
 }
 
-#[derive(FromArgs)]
-#[argh(help_triggers("-h", "--help", "help"))]
-/// CLI to track usage times for pc and applications
-struct Params {
-    /// to get the current version number
-    #[argh(switch, short = 'v')]
-    version: bool,
-
-    /// param: <[on|off|switch]>. set counter status, Use one of the following options
-    #[argh(option)]
-    state: Option<String>,
-
-    /// param: <digit>. change the size of the storage
-    #[argh(option)]
-    storage: Option<u16>,
-
-    /// get the settings of this application
-    #[argh(switch, short = 's')]
-    settings: bool,
-
-    /// param: <app_name>. enables notification mode for an application
-    #[argh(option)]
-    add_notif: Option<String>,
-
-    /// param: <time>. indicates the time in minutes before a notification is sent
-    #[argh(option)]
-    notif_time: Option<u16>,
-
-    /// param: <app_name>. removes notification functionality for an application
-    #[argh(option)]
-    del_notif: Option<String>,
-
-    /// displays the list of notifications
-    #[argh(switch)]
-    print_notif: bool,
-
-    /// launch update
-    #[argh(switch, short = 'u')]
-    update: bool,
-
-    /// param: <app_name>. add a application
-    #[argh(option)]
-    add: Option<String>,
-
-    /// param: <app_name>. delete a application
-    #[argh(option)]
-    del: Option<String>,
-
-    /// to retrieve data either for a day's info with [daydata] or an application's info with [app-<name>]
-    #[argh(option, short = 'q')]
-    query: Option<String>,
+fn main() -> ExitCode {
+    let param: Params = argh::from_env();
 
-    /// param: <date>. select the date of the retrieved data, foramt : YYYY-mm-dd. you can also use keywords such as yesterday, last_week or a day of the week (monday…).
-    #[argh(option)]
-    date: Option<String>,
+    match run(param) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error : {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    /// param: <digit>. select the number of day of the retrieved data
-    #[argh(option, short = 'n', default = "0")]
-    number: u16,
+fn run(param: Params) -> Result<()> {
+    let output = parse_output(param.output.as_deref(), Output::Plain)?;
 
-    /// inverts the result for an application
-    #[argh(switch, short = 'r')]
-    reverse: bool,
+    if param.version {
+        println!("current version : {}", VERSION_NUMBER);
+    }
 
-    /// color of tui. Choice : Black, Red, Green, Yellow, Blue, Magenta, Cyan, Gray, DarkGray, LightRed, LightGreen, LightYellow, LightBlue, LightMagenta, LightCyan, White
-    #[argh(option, short = 'c')]
-    color: Option<String>,
+    let database = Database::new()?;
 
-    /// launch tui
-    #[argh(switch, short = 't')]
-    tui: bool,
+    match param.command {
+        Some(command) => run_command(database, command, output),
+        None => run_flags(database, param, output),
+    }
 }
 
-fn main() -> io::Result<()> {
-    let param: Params = argh::from_env();
-
-    let mut flag = true;
-
-    let database = Database::new().expect("Unable to work with database");
-
-    if param.version {
-        println!("current version : {}", VERSION_NUMBER);
-        flag = false;
+fn parse_output(output: Option<&str>, default: Output) -> Result<Output> {
+    match output {
+        Some(output) => Output::parse(output)
+            .ok_or_else(|| Error::usage("the output must be one of [plain|table|json|csv]")),
+        None => Ok(default),
     }
+}
 
-    if let Some(mode) = param.state {
-        if mode == "on" || mode == "off" {
-            database
-                .change_settings("state", &mode)
-                .expect("state : Unable to work with database");
-        } else if mode == "switch" {
-            database
-                .switch_state()
-                .expect("state : Unable to work with database");
-        } else {
-            eprintln!("Error : there are only three possible modes [on|off|switch]");
+fn run_command(database: Database, command: Command, output: Output) -> Result<()> {
+    match command {
+        Command::Track(track) => {
+            if track.daemon {
+                start_daemon(database, track.interval)
+            } else {
+                database.update()
+            }
         }
-        flag = false;
+        Command::App(app) => match app.action {
+            AppAction::Add(add) => database.add_app(add.name),
+            AppAction::Del(del) => database.del_app(del.name),
+            AppAction::List(list) => {
+                database.print_apps(parse_output(list.output.as_deref(), output)?)
+            }
+        },
+        Command::Matcher(matcher) => match matcher.action {
+            MatcherAction::Add(add) => {
+                let (kind, pattern) = split_matcher(&add.matcher)?;
+                database.add_matcher(&add.name, kind, pattern)
+            }
+            MatcherAction::Del(del) => {
+                let matcher = del.matcher.as_deref().map(split_matcher).transpose()?;
+                database.del_matcher(&del.name, matcher)
+            }
+            MatcherAction::List(list) => {
+                database.print_matchers(parse_output(list.output.as_deref(), output)?)
+            }
+        },
+        Command::Group(group) => match group.action {
+            GroupAction::Add(add) => {
+                let apps: Vec<&str> = add.apps.iter().map(String::as_str).collect();
+                if apps.is_empty() {
+                    return Err(Error::usage("a group needs at least one application"));
+                }
+                database.add_group(&add.name, &apps)
+            }
+            GroupAction::Del(del) => {
+                let apps: Vec<&str> = del.apps.iter().map(String::as_str).collect();
+                database.del_group(&del.name, &apps)
+            }
+            GroupAction::List(list) => {
+                database.print_groups(parse_output(list.output.as_deref(), output)?)
+            }
+        },
+        Command::Notif(notif) => match notif.action {
+            NotifAction::Add(add) => database.add_notif(&Notif {
+                app: add.name,
+                minutes: add.minutes,
+                period: if add.week { Period::Week } else { Period::Day },
+                days: parse_days(add.days.as_deref())?,
+                warning_minutes: add.warning,
+                repeat_minutes: add.repeat,
+                critical_after: add.critical_after,
+                message: add.message,
+                action: match (add.kill, &add.command) {
+                    (true, Some(_)) => {
+                        return Err(Error::usage("you must use either [--kill] or [--command]"));
+                    }
+                    (true, None) => Some(Action::Kill),
+                    (false, Some(_)) => Some(Action::Command),
+                    (false, None) => None,
+                },
+                command: add.command,
+                grace_minutes: add.grace,
+            }),
+            NotifAction::Del(del) => match (del.minutes, del.days.is_some() || del.week) {
+                (Some(minutes), true) => database.del_limit(&Notif {
+                    period: if del.week { Period::Week } else { Period::Day },
+                    days: parse_days(del.days.as_deref())?,
+                    ..Notif::new(&del.name, minutes)
+                }),
+                (None, true) => Err(Error::usage(
+                    "the time of the limit is needed with [--days] or [--week]",
+                )),
+                (minutes, false) => database.del_notif(&del.name, minutes),
+            },
+            NotifAction::Snooze(snooze) => database.snooze(&snooze.name, &snooze.reason),
+            NotifAction::List(list) => {
+                database.print_notif(parse_output(list.output.as_deref(), output)?)
+            }
+        },
+        Command::Query(query) => {
+            let output = parse_output(query.output.as_deref(), output)?;
+            run_query(
+                &database,
+                &query.kind,
+                query.name,
+                query.date,
+                query.number,
+                query.reverse,
+                query.seconds,
+                output,
+            )
+        }
+        Command::Settings(settings) => match settings.action {
+            SettingsAction::Get(get) => {
+                database.display_settings(parse_output(get.output.as_deref(), output)?)
+            }
+            SettingsAction::Set(set) => change_setting(&database, &set.attribute, &set.value),
+        },
+        Command::Export(export) => export_file(
+            &database,
+            &export.path,
+            export.format.as_deref(),
+            export.date,
+            export.number,
+        ),
+        Command::Import(import) => import_file(
+            &database,
+            &import.path,
+            import.format.as_deref(),
+            import.merge.as_deref(),
+            import.dry_run,
+        ),
+        Command::Tui(tui) => start_tui(database, tui.color),
     }
+}
 
-    if let Some(number) = param.storage {
-        database
-            .change_settings("storage_size", &number.to_string())
-            .expect("storage : Unable to work with database");
-        flag = false;
+// The flags of the previous versions, kept for the existing cron lines and scripts
+fn run_flags(database: Database, param: Params, output: Output) -> Result<()> {
+    let mut flag = !param.version;
+
+    let settings = [
+        ("state", param.state),
+        ("storage", param.storage.map(|n| n.to_string())),
+        ("archive", param.archive.map(|n| n.to_string())),
+        ("idle", param.idle.map(|n| n.to_string())),
+        ("idle-apps", param.idle_apps),
+        ("foreground", param.foreground),
+        ("timezone", param.timezone),
+        ("day-start", param.day_start),
+    ];
+    for (attribute, value) in settings {
+        if let Some(value) = value {
+            change_setting(&database, attribute, &value)?;
+            flag = false;
+        }
     }
 
     if param.settings {
-        database
-            .display_settings()
-            .expect("settings : Unable to work with database");
+        database.display_settings(output)?;
         flag = false;
     }
 
     match (param.add_notif, param.notif_time) {
+        // The flag updates the daily limit with the same time on every day, its log and
+        // the other limits of the application are kept
         (Some(name), Some(time)) => {
-            database
-                .add_notif(&name, time)
-                .expect("notif_app : Unable to work with database");
+            database.add_notif(&Notif {
+                repeat_minutes: param.notif_repeat.unwrap_or(0),
+                critical_after: param.notif_critical,
+                ..Notif::new(&name, time)
+            })?;
             flag = false;
         }
-        (None, None) => (),
+        (None, None) if param.notif_repeat.is_none() && param.notif_critical.is_none() => (),
         _ => {
-            eprintln!(
-                "Error : you must use the arguments [--notif_app] and [--notif_time] at the same time"
-            );
-            flag = false;
+            return Err(Error::usage(
+                "you must use the arguments [--add-notif] and [--notif-time] at the same time, [--notif-repeat] and [--notif-critical] go with them",
+            ));
         }
     }
 
     if let Some(name) = param.del_notif {
-        database
-            .del_notif(&name)
-            .expect("del_notif_app : Unable to work with database");
+        database.del_notif(&name, None)?;
         flag = false;
     }
 
     if param.print_notif {
-        database
-            .print_notif()
-            .expect("print_notif : Unable to work with database");
+        database.print_notif(output)?;
         flag = false;
     }
 
     if param.update {
-        database
-            .update()
-            .expect("update : Unable to work with database");
+        database.update()?;
         flag = false;
     }
 
+    if param.daemon {
+        return start_daemon(database, param.interval);
+    }
+
     if let Some(name) = param.add {
-        database
-            .add_app(name)
-            .expect("add : Unable to work with database");
+        database.add_app(name)?;
         flag = false;
     }
 
     if let Some(name) = param.del {
-        database
-            .del_app(name)
-            .expect("del : Unable to work with database");
+        database.del_app(name)?;
         flag = false;
     }
 
-    if let Some(query) = param.query {
-        if query == "daydata" {
-            let (date, number) = get_value_or_default(TypeRequest::Day, param.date, param.number);
-            database
-                .print_day_data(date, number, param.reverse)
-                .expect("daydata : Unable to work with database");
-        } else if query.len() >= 5 && query[0..4] == *"app-" {
-            let (date, number) = get_value_or_default(TypeRequest::App, param.date, param.number);
-            database
-                .print_app_data(query[4..].to_string(), date, number, param.reverse)
-                .expect("app : Unable to work with database");
-        } else {
-            eprintln!("Query error. Please use [daydata] or [app-<name>] as query parameter");
+    let matcher = param.matcher.as_deref().map(split_matcher).transpose()?;
+
+    match (&param.add_matcher, matcher) {
+        (Some(name), Some((kind, pattern))) => {
+            database.add_matcher(name, kind, pattern)?;
+            flag = false;
         }
+        (Some(_), None) => {
+            return Err(Error::usage(
+                "you must use the arguments [--add-matcher] and [--matcher] at the same time",
+            ));
+        }
+        _ => (),
+    }
+
+    if let Some(name) = param.del_matcher {
+        database.del_matcher(&name, matcher)?;
         flag = false;
     }
 
-    if param.tui {
-        let color = match param.color {
-            Some(color) => Color::from_str(&color).unwrap_or(Color::LightBlue),
-            _ => Color::LightBlue,
+    if param.print_matcher {
+        database.print_matchers(output)?;
+        flag = false;
+    }
+
+    let group_apps: Vec<&str> = param
+        .group_apps
+        .as_deref()
+        .map(|apps| {
+            apps.split(',')
+                .map(str::trim)
+                .filter(|a| !a.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(name) = &param.add_group {
+        if group_apps.is_empty() {
+            return Err(Error::usage(
+                "you must use the arguments [--add-group] and [--group-apps] at the same time",
+            ));
+        }
+        database.add_group(name, &group_apps)?;
+        flag = false;
+    }
+
+    if let Some(name) = &param.del_group {
+        database.del_group(name, &group_apps)?;
+        flag = false;
+    }
+
+    if param.print_group {
+        database.print_groups(output)?;
+        flag = false;
+    }
+
+    if let Some(path) = &param.import {
+        import_file(
+            &database,
+            path,
+            param.format.as_deref(),
+            param.merge.as_deref(),
+            param.dry_run,
+        )?;
+        flag = false;
+    }
+
+    if let Some(path) = &param.export {
+        export_file(
+            &database,
+            path,
+            param.format.as_deref(),
+            param.date.clone(),
+            param.number,
+        )?;
+        flag = false;
+    }
+
+    if let Some(query) = param.query {
+        let (kind, name) = if let Some(name) = query.strip_prefix("app-") {
+            ("app", Some(name.to_string()))
+        } else if let Some(name) = query.strip_prefix("sessions-") {
+            ("sessions", Some(name.to_string()))
+        } else if query == "daydata" {
+            ("day", None)
+        } else {
+            (query.as_str(), None)
         };
-        tui::start(database, color)?;
+        run_query(
+            &database,
+            kind,
+            name,
+            param.date,
+            param.number,
+            param.reverse,
+            param.seconds,
+            output,
+        )?;
         flag = false;
     }
 
+    if param.tui {
+        return start_tui(database, param.color);
+    }
+
     if flag {
         println!("run `time_app_tracker --help` for help");
     }
@@ -215,13 +366,206 @@ fn main() -> io::Result<()> {
     Ok(())
 }
 
+fn parse_days(days: Option<&str>) -> Result<Days> {
+    match days {
+        Some(days) => {
+            Days::parse(days).ok_or_else(|| Error::usage(format!("invalid days : {}", days)))
+        }
+        None => Ok(Days::ALL),
+    }
+}
+
+fn split_matcher(matcher: &str) -> Result<(&str, &str)> {
+    matcher
+        .split_once(':')
+        .ok_or_else(|| Error::usage("the matcher must be given as kind:pattern, like comm:firefox"))
+}
+
+fn change_setting(database: &Database, attribute: &str, value: &str) -> Result<()> {
+    let on_off = || {
+        if value == "on" || value == "off" {
+            Ok(())
+        } else {
+            Err(Error::usage("there are only two possible modes [on|off]"))
+        }
+    };
+    let number = || {
+        value
+            .parse::<u16>()
+            .map(|_| ())
+            .map_err(|_| Error::usage(format!("the {} must be a number", attribute)))
+    };
+
+    let name = match attribute {
+        "state" if value == "switch" => return database.switch_state(),
+        "state" => {
+            if value != "on" && value != "off" {
+                return Err(Error::usage(
+                    "there are only three possible modes [on|off|switch]",
+                ));
+            }
+            "state"
+        }
+        "storage" => {
+            number()?;
+            if value
+                .parse::<u16>()
+                .is_ok_and(|days| days < NUMBER_DAYS_IN_WEEK)
+                && database
+                    .get_notifs()?
+                    .iter()
+                    .any(|notif| notif.period == Period::Week)
+            {
+                return Err(Error::usage(format!(
+                    "the weekly limits need a storage of at least {} days",
+                    NUMBER_DAYS_IN_WEEK
+                )));
+            }
+            "storage_size"
+        }
+        "archive" => {
+            number()?;
+            "archive_size"
+        }
+        "idle" => {
+            number()?;
+            "idle_threshold"
+        }
+        "idle-apps" => {
+            on_off()?;
+            "idle_apps"
+        }
+        "foreground" => {
+            on_off()?;
+            "foreground"
+        }
+        "timezone" => {
+            if Zone::parse(value).is_none() {
+                return Err(Error::usage(
+                    "the time zone must be [local] or a name like Europe/Paris",
+                ));
+            }
+            "timezone"
+        }
+        "day-start" => {
+            let Ok(time) = NaiveTime::parse_from_str(value, "%H:%M") else {
+                return Err(Error::usage("the start of the day must be given as HH:MM"));
+            };
+            // The time of each hour is kept for the day in which the hour starts
+            if time.minute() != 0 {
+                return Err(Error::usage("the day must start on the hour, like 04:00"));
+            }
+            "day_start"
+        }
+        _ => {
+            return Err(Error::usage(
+                "the setting must be one of [state|storage|archive|idle|idle-apps|foreground|timezone|day-start]",
+            ));
+        }
+    };
+
+    database.change_settings(name, value)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_query(
+    database: &Database,
+    kind: &str,
+    name: Option<String>,
+    date: Option<String>,
+    number: u16,
+    reverse: bool,
+    seconds: bool,
+    output: Output,
+) -> Result<()> {
+    let today = database.today()?;
+    match (kind, name) {
+        ("timeline", None) => {
+            let (date, number) = get_value_or_default(TypeRequest::Day, date, number, today)?;
+            database.print_timeline(date, number, reverse, output)
+        }
+        ("day", None) => {
+            let (date, number) = get_value_or_default(TypeRequest::Day, date, number, today)?;
+            database.print_day_data(date, number, reverse, seconds, output)
+        }
+        ("app", Some(name)) if !name.is_empty() => {
+            let (date, number) = get_value_or_default(TypeRequest::App, date, number, today)?;
+            database.print_app_data(name, date, number, reverse, seconds, output)
+        }
+        ("sessions", Some(name)) if !name.is_empty() => {
+            let (date, number) = get_value_or_default(TypeRequest::App, date, number, today)?;
+            database.print_sessions(name, date, number, reverse, seconds, output)
+        }
+        _ => Err(Error::usage(
+            "Please use [day], [timeline], [app <name>] or [sessions <name>] as query",
+        )),
+    }
+}
+
+fn export_file(
+    database: &Database,
+    path: &str,
+    format: Option<&str>,
+    date: Option<String>,
+    number: u16,
+) -> Result<()> {
+    let format = Format::from_str(format.unwrap_or("csv"))
+        .map_err(|()| Error::usage("the format must be one of [csv|json|ndjson]"))?;
+
+    let today = database.today()?;
+    let (date, _) = get_value_or_default(TypeRequest::Day, date, 0, today)?;
+    let first_day = (number > 0).then(|| date - Duration::days(number as i64 - 1));
+
+    let records = database.get_records(first_day, date)?;
+    export::export(&records, format, path).map_err(|e| Error::File(path.to_string(), e))
+}
+
+fn import_file(
+    database: &Database,
+    path: &str,
+    format: Option<&str>,
+    merge: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let source = Source::from_str(format.unwrap_or("csv")).map_err(|()| {
+        Error::usage("the format must be one of [csv|json|ndjson|activitywatch|arbtt]")
+    })?;
+    let merge = Merge::parse(merge.unwrap_or("add"))
+        .ok_or_else(|| Error::usage("the merge must be one of [add|max|replace]"))?;
+
+    let data = import::import(path, source, database.zone()?)
+        .map_err(|e| Error::File(path.to_string(), e))?;
+    let summary = database.import(&data, merge, dry_run)?;
+    println!("{}", summary);
+    Ok(())
+}
+
+fn start_daemon(database: Database, interval: Option<u16>) -> Result<()> {
+    let interval = interval.unwrap_or(DEFAULT_DAEMON_INTERVAL);
+    if interval == 0 {
+        return Err(Error::usage(
+            "the interval of the daemon must be at least one second",
+        ));
+    }
+    daemon::start(database, interval)
+}
+
+fn start_tui(database: Database, color: Option<String>) -> Result<()> {
+    let color = match color {
+        Some(color) => Color::from_str(&color).unwrap_or(Color::LightBlue),
+        _ => Color::LightBlue,
+    };
+    tui::start(database, color)
+}
+
 fn get_value_or_default(
     type_request: TypeRequest,
     date: Option<String>,
     number: u16,
-) -> (NaiveDate, u16) {
+    today: NaiveDate,
+) -> Result<(NaiveDate, u16)> {
     let date = date.clone().unwrap_or_else(|| String::from("today"));
-    let mut date_res = Utc::now().date_naive();
+    let mut date_res = today;
 
     let mut number_res: u16;
     if number == 0 {
@@ -235,33 +579,34 @@ fn get_value_or_default(
 
     match date.to_lowercase().as_str() {
         "today" | "t" => (),
-        "yesterday" | "y" => date_res = Utc::now().date_naive() - Duration::days(1),
-        "monday" | "mon" => date_res = weekday_to_date(Weekday::Mon),
-        "tuesday" | "tue" => date_res = weekday_to_date(Weekday::Tue),
-        "wednesday" | "wed" => date_res = weekday_to_date(Weekday::Wed),
-        "thursday" | "thu" => date_res = weekday_to_date(Weekday::Thu),
-        "friday" | "fri" => date_res = weekday_to_date(Weekday::Fri),
-        "saturday" | "sat" => date_res = weekday_to_date(Weekday::Sat),
-        "sunday" | "sun" => date_res = weekday_to_date(Weekday::Sun),
+        "yesterday" | "y" => date_res = today - Duration::days(1),
+        "monday" | "mon" => date_res = weekday_to_date(today, Weekday::Mon),
+        "tuesday" | "tue" => date_res = weekday_to_date(today, Weekday::Tue),
+        "wednesday" | "wed" => date_res = weekday_to_date(today, Weekday::Wed),
+        "thursday" | "thu" => date_res = weekday_to_date(today, Weekday::Thu),
+        "friday" | "fri" => date_res = weekday_to_date(today, Weekday::Fri),
+        "saturday" | "sat" => date_res = weekday_to_date(today, Weekday::Sat),
+        "sunday" | "sun" => date_res = weekday_to_date(today, Weekday::Sun),
         "last_week" | "lw" => {
-            date_res = weekday_to_date(Weekday::Sun);
+            date_res = weekday_to_date(today, Weekday::Sun);
             if number == 0 && type_request == TypeRequest::App {
                 number_res = 7
             };
         }
         d => {
-            if let Ok(date_parse) = NaiveDate::parse_from_str(d, "%Y-%m-%d") {
-                date_res = date_parse;
-            }
+            date_res = NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| {
+                Error::Parse(format!(
+                    "the date {} must be given as YYYY-mm-dd or a keyword such as yesterday",
+                    d
+                ))
+            })?;
         }
     }
 
-    (date_res, number_res)
+    Ok((date_res, number_res))
 }
 
-fn weekday_to_date(day: Weekday) -> NaiveDate {
-    let today = Utc::now().date_naive();
-
+fn weekday_to_date(today: NaiveDate, day: Weekday) -> NaiveDate {
     let mut days_to_subtract = match today.weekday().num_days_from_monday() {
         n if n >= day.num_days_from_monday() => n - day.num_days_from_monday(),
         n => n + 7 - day.num_days_from_monday(),
@@ -273,3 +618,18 @@ fn weekday_to_date(day: Weekday) -> NaiveDate {
 
     today - Duration::days(days_to_subtract as i64)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matchers_need_a_kind() {
+        assert_eq!(
+            split_matcher("cmdline:--type=renderer").unwrap(),
+            ("cmdline", "--type=renderer")
+        );
+        assert!(split_matcher("cmdline").is_err());
+        assert!(split_matcher("firefox").is_err());
+    }
+}
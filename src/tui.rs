@@ -2,23 +2,24 @@
 Ajouter les paramètres
 Montrer une liste avec scroll au lieu de devoir taper les noms d'appli
 */
-use chrono::{Datelike, Duration, NaiveDate, Utc};
+use chrono::{Datelike, Duration, NaiveDate};
 use crossterm::event::{self, Event, KeyCode, KeyEvent};
 use ratatui::{
     DefaultTerminal, Frame,
     layout::{Alignment, Constraint, Flex, Layout, Rect},
     style::{Color, Modifier},
-    text::Line,
+    text::{Line, Span},
     widgets::{Bar, BarChart, BarGroup, Block, BorderType, Borders, Clear, Paragraph},
 };
-use std::{io, str::FromStr};
 use tui_input::{Input, backend::crossterm::EventHandler};
 
-use crate::database::{Database, Stat, TimeApp};
+use crate::database::{Database, Notif, Stat, Time, TimeApp, timeline_level};
+use crate::error::{Error, Result};
 
-pub fn start(database: Database, color: Color) -> io::Result<()> {
+pub fn start(database: Database, color: Color) -> Result<()> {
+    let mut app = App::new(database, color)?;
     let mut terminal = ratatui::init();
-    let app_result = App::new(database, color).run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     ratatui::restore();
     app_result
 }
@@ -28,29 +29,38 @@ struct App {
     mode: Mode,
     option_day: OptionDay,
     option_app: OptionApp,
+    option_notif: OptionNotif,
     show_input: TypeInput,
     input: Input,
     show_help: bool,
+    // Displays the time spent in the foreground instead of the running time
+    foreground: bool,
     color: Color,
+    // Error of the last data retrieval, displayed in the status line
+    status: Option<String>,
     exit: bool,
 }
 
 impl App {
-    fn new(database: Database, color: Color) -> App {
-        App {
+    fn new(database: Database, color: Color) -> Result<App> {
+        let today = database.today()?;
+        Ok(App {
             database,
             mode: Mode::Day,
-            option_day: OptionDay::new(),
-            option_app: OptionApp::new(),
+            option_day: OptionDay::new(today),
+            option_app: OptionApp::new(today),
+            option_notif: OptionNotif::new(),
             show_input: TypeInput::None,
             input: Input::new(String::new()),
             show_help: false,
+            foreground: false,
             color,
+            status: None,
             exit: false,
-        }
+        })
     }
 
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
         while !self.exit {
             terminal.draw(|frame| self.render(frame))?;
             self.handle_events()?;
@@ -70,22 +80,37 @@ impl App {
         let vertical = Layout::vertical([
             Constraint::Percentage(9),
             Constraint::Percentage(9),
-            Constraint::Percentage(82),
+            Constraint::Fill(1),
+            Constraint::Length(1),
         ]);
-        let [options, stats, chart] = vertical.areas(block.inner(frame.area()));
+        let [options, stats, chart, status] = vertical.areas(block.inner(frame.area()));
 
         frame.render_widget(block, frame.area());
 
         match self.mode {
-            Mode::Day => self.option_day.render(frame, options),
+            Mode::Day | Mode::Timeline => self.option_day.render(frame, options, &self.mode),
             Mode::App => self.option_app.render(frame, options),
+            Mode::Notif => self.option_notif.render(frame, options),
         };
 
         if self.mode == Mode::App {
             self.stats(frame, stats);
         }
 
-        self.chart(frame, chart);
+        if self.mode == Mode::Timeline {
+            self.timeline(frame, chart);
+        } else if self.mode == Mode::Notif {
+            self.notifications(frame, chart);
+        } else if self.mode == Mode::App && self.option_app.sessions {
+            self.sessions(frame, chart);
+        } else {
+            self.chart(frame, chart);
+        }
+
+        if let Some(error) = &self.status {
+            let line = Line::styled(format!(" Error : {} ", error), (Color::Red, Modifier::BOLD));
+            frame.render_widget(line, status);
+        }
 
         if self.show_input != TypeInput::None {
             App::display_input(frame, self.input.value());
@@ -96,9 +121,10 @@ impl App {
         }
     }
 
-    fn handle_events(&mut self) -> io::Result<()> {
+    fn handle_events(&mut self) -> Result<()> {
         let event = event::read()?;
         if let Event::Key(key) = event {
+            self.status = None;
             match self.show_input {
                 TypeInput::None => self.handle_key_event(key),
                 _ => match key.code {
@@ -126,12 +152,18 @@ impl App {
             Mode::Day => match key_event.code {
                 KeyCode::Char('q') | KeyCode::Esc => self.exit(),
                 KeyCode::Char('m') => self.mode = Mode::App,
+                KeyCode::Char('f') => self.foreground = !self.foreground,
+                KeyCode::Char('g') => self.option_day.switch_groups(),
                 KeyCode::Char('r') => self.option_day.switch_reverse(),
                 KeyCode::Char('d') => {
                     self.show_input = TypeInput::Date;
                     self.input = Input::new(self.option_day.date.to_string());
                 }
-                KeyCode::Char('t') => self.option_day.date = Utc::now().date_naive(),
+                KeyCode::Char('t') => {
+                    if let Some(today) = self.today() {
+                        self.option_day.date = today;
+                    }
+                }
                 KeyCode::Up | KeyCode::Char('k') => self.option_day.date += Duration::days(1),
                 KeyCode::Down | KeyCode::Char('j') => self.option_day.date -= Duration::days(1),
                 KeyCode::Right | KeyCode::Char('l') => self.option_day.index_bar += 1,
@@ -144,10 +176,32 @@ impl App {
                 }
                 _ => {}
             },
+            Mode::Timeline => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.exit(),
+                KeyCode::Char('m') => self.mode = Mode::Notif,
+                KeyCode::Char('d') => {
+                    self.show_input = TypeInput::Date;
+                    self.input = Input::new(self.option_day.date.to_string());
+                }
+                KeyCode::Char('t') => {
+                    if let Some(today) = self.today() {
+                        self.option_day.date = today;
+                    }
+                }
+                KeyCode::Up | KeyCode::Char('k') => self.option_day.date += Duration::days(1),
+                KeyCode::Down | KeyCode::Char('j') => self.option_day.date -= Duration::days(1),
+                KeyCode::Char('?') => {
+                    self.show_input = TypeInput::None;
+                    self.show_help = true;
+                }
+                _ => {}
+            },
             Mode::App => match key_event.code {
                 KeyCode::Char('q') | KeyCode::Esc => self.exit(),
-                KeyCode::Char('m') => self.mode = Mode::Day,
+                KeyCode::Char('m') => self.mode = Mode::Timeline,
+                KeyCode::Char('f') => self.foreground = !self.foreground,
                 KeyCode::Char('r') => self.option_app.switch_reverse(),
+                KeyCode::Char('s') => self.option_app.switch_sessions(),
                 KeyCode::Char('d') => {
                     self.show_input = TypeInput::Date;
                     self.input = Input::new(self.option_app.date.to_string());
@@ -155,7 +209,11 @@ impl App {
                 KeyCode::Char('n') => self.show_input = TypeInput::Number,
                 KeyCode::Char('a') => self.show_input = TypeInput::App,
 
-                KeyCode::Char('t') => self.option_app.date = Utc::now().date_naive(),
+                KeyCode::Char('t') => {
+                    if let Some(today) = self.today() {
+                        self.option_app.date = today;
+                    }
+                }
                 KeyCode::Up | KeyCode::Char('k') => self.option_app.date += Duration::days(1),
                 KeyCode::Down | KeyCode::Char('j') => self.option_app.date -= Duration::days(1),
                 KeyCode::Right | KeyCode::Char('l') => self.option_app.index_bar += 1,
@@ -168,20 +226,69 @@ impl App {
                 }
                 _ => {}
             },
+            Mode::Notif => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.exit(),
+                KeyCode::Char('m') => self.mode = Mode::Day,
+                KeyCode::Char('a') => self.show_input = TypeInput::Notif,
+                KeyCode::Char('x') | KeyCode::Delete => self.del_notif(),
+                KeyCode::Up | KeyCode::Char('k') if self.option_notif.selected > 0 => {
+                    self.option_notif.selected -= 1
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.option_notif.selected += 1,
+                KeyCode::Char('?') => {
+                    self.show_input = TypeInput::None;
+                    self.show_help = true;
+                }
+                _ => {}
+            },
         }
     }
 
+    fn today(&mut self) -> Option<NaiveDate> {
+        self.report(self.database.today())
+    }
+
+    // The errors are displayed in the status line instead of closing the interface
+    fn report<T>(&mut self, result: Result<T>) -> Option<T> {
+        result.map_err(|e| self.status = Some(e.to_string())).ok()
+    }
+
     fn exit(&mut self) {
         self.exit = true;
     }
 
+    // The input is the name of the application followed by the minutes of the limit
+    fn add_notif(&mut self, input: &str) {
+        let limit = input
+            .trim()
+            .rsplit_once(' ')
+            .and_then(|(app, minutes)| Some((app.trim(), minutes.parse::<u16>().ok()?)));
+        let result = match limit {
+            Some((app, minutes)) => self.database.add_notif(&Notif::new(app, minutes)),
+            None => Err(Error::usage(
+                "type the application and the minutes of the limit, e.g. pc 120",
+            )),
+        };
+        self.report(result);
+    }
+
+    fn del_notif(&mut self) {
+        let Some(notifs) = self.report(self.database.get_notifs()) else {
+            return;
+        };
+        if let Some(notif) = notifs.get(self.option_notif.selected) {
+            let result = self.database.del_limit(notif);
+            self.report(result);
+        }
+    }
+
     fn chart(&mut self, frame: &mut Frame, area: Rect) {
-        let mut data = self.get_data();
+        let mut data = self.report(self.get_data()).unwrap_or_default();
         let mut bars: Vec<Bar> = Vec::new();
 
-        let max_value = data.iter().max_by_key(|d| d.min_total).map(|d| d.min_total);
+        let max_value = data.iter().max_by_key(|d| d.sec_total).map(|d| d.sec_total);
         match self.mode {
-            Mode::Day => data.sort_by_key(|d| d.min_total),
+            Mode::Day | Mode::Timeline | Mode::Notif => data.sort_by_key(|d| d.sec_total),
             Mode::App => data.sort_by_key(|d| d.date),
         }
         if !self.is_reverse() {
@@ -192,10 +299,11 @@ impl App {
             bars.push(
                 Bar::default()
                     .label(match self.mode {
-                        Mode::Day => d.name.into(),
+                        Mode::Day | Mode::Timeline | Mode::Notif => d.name.into(),
+                        Mode::App if d.month => d.date.format("%Y-%m").to_string().into(),
                         Mode::App => d.date.to_string()[5..].to_string().into(),
                     })
-                    .value(d.min_total as u64)
+                    .value(d.sec_total)
                     .text_value(d.time.to_string()),
             );
         }
@@ -207,6 +315,18 @@ impl App {
         ]);
         let [left, area, right] = horizontal.areas(area);
 
+        if bars.is_empty() {
+            let empty = Paragraph::new("No data")
+                .block(
+                    Block::bordered()
+                        .title("Chart")
+                        .border_type(BorderType::Rounded),
+                )
+                .centered();
+            frame.render_widget(empty, area);
+            return;
+        }
+
         let mut width = area.width;
         let (index_start, index_end) = self.slice_bars(bars.len(), &mut width);
 
@@ -220,10 +340,14 @@ impl App {
 
         let chart = BarChart::default()
             .data(BarGroup::default().bars(&bars[index_start..=index_end]))
-            .max(max_value.unwrap_or(60 * 24) as u64)
+            .max(max_value.unwrap_or(60 * 60 * 24))
             .block(
                 Block::bordered()
-                    .title("Chart")
+                    .title(if self.foreground {
+                        "Chart (foreground)"
+                    } else {
+                        "Chart"
+                    })
                     .border_type(BorderType::Rounded),
             )
             .bar_width(width)
@@ -234,7 +358,7 @@ impl App {
 
     fn slice_bars(&mut self, number_elements: usize, widths: &mut u16) -> (usize, usize) {
         let index_start = match self.mode {
-            Mode::Day => &mut self.option_day.index_bar,
+            Mode::Day | Mode::Timeline | Mode::Notif => &mut self.option_day.index_bar,
             Mode::App => &mut self.option_app.index_bar,
         };
 
@@ -271,7 +395,7 @@ impl App {
         frame.render_widget(&arrow, arrow3);
     }
 
-    fn stats(&self, frame: &mut Frame, area: Rect) {
+    fn stats(&mut self, frame: &mut Frame, area: Rect) {
         let horizontal = Layout::horizontal([
             Constraint::Length(2),
             Constraint::Fill(1),
@@ -292,7 +416,8 @@ impl App {
         ]);
         let [_, max, min, sum, mean, _] = horizontal.areas(block.inner(area));
 
-        let stat = Stat::new(&self.get_data());
+        let data = self.report(self.get_data()).unwrap_or_default();
+        let stat = Stat::new(&data);
         frame.render_widget(block, area);
         frame.render_widget(Line::from(format!(" Max : {} ", stat.max)).centered(), max);
         frame.render_widget(Line::from(format!(" Min : {} ", stat.min)).centered(), min);
@@ -303,30 +428,185 @@ impl App {
         );
     }
 
-    fn get_data(&self) -> Vec<TimeApp> {
-        match &self.mode {
-            Mode::Day => self.database.get_time_day(self.option_day.date).unwrap(),
-            Mode::App => self
-                .database
-                .get_time_app(
-                    &self.option_app.app,
-                    self.option_app.date,
-                    self.option_app.number,
+    // One strip of 24 hours per application, each hour is shaded by the share of it
+    // during which the application was running
+    fn timeline(&mut self, frame: &mut Frame, area: Rect) {
+        let horizontal = Layout::horizontal([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ]);
+        let [_, area, _] = horizontal.areas(area);
+
+        let Some(timeline) = self.report(self.database.get_timeline(self.option_day.date)) else {
+            return;
+        };
+        let width = timeline
+            .values
+            .iter()
+            .map(|v| v.name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut header = format!("{:width$}  ", "");
+        for hour in timeline.hours() {
+            header.push_str(&format!("{:<3}", hour));
+        }
+        let mut lines = vec![Line::from(header), Line::default()];
+
+        for v in &timeline.values {
+            let mut spans = vec![Span::raw(format!("{:width$} |", v.name))];
+            for hour in timeline.hours() {
+                let level = timeline_level(v.hours[hour]).to_string();
+                spans.push(Span::styled(level.repeat(3), self.color));
+            }
+            spans.push(Span::raw("|"));
+            lines.push(Line::from(spans));
+        }
+
+        if timeline.values.is_empty() {
+            lines.push(Line::from("No data").centered());
+        }
+
+        let timeline = Paragraph::new(lines).block(
+            Block::bordered()
+                .title("Timeline")
+                .border_type(BorderType::Rounded),
+        );
+        frame.render_widget(timeline, area);
+    }
+
+    // Runs of the observed application, scrolled with the same keys as the graph
+    fn sessions(&mut self, frame: &mut Frame, area: Rect) {
+        let horizontal = Layout::horizontal([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ]);
+        let [_, area, _] = horizontal.areas(area);
+
+        let sessions = self.database.get_sessions(
+            &self.option_app.app,
+            self.option_app.date,
+            self.option_app.number,
+        );
+        let mut sessions = self.report(sessions).unwrap_or_default();
+        if self.option_app.reverse {
+            sessions.reverse();
+        }
+
+        let longest = sessions.iter().map(|s| s.sec_total).max().unwrap_or(0);
+        let mut lines = vec![
+            Line::styled(
+                format!(
+                    "Launches : {} | Longest : {}",
+                    sessions.len(),
+                    Time::new(longest)
+                ),
+                (self.color, Modifier::BOLD),
+            ),
+            Line::default(),
+        ];
+        lines.extend(
+            sessions
+                .iter()
+                .skip(
+                    self.option_app
+                        .index_bar
+                        .min(sessions.len().saturating_sub(1)),
                 )
-                .unwrap(),
+                .map(|s| Line::from(s.to_string())),
+        );
+
+        if sessions.is_empty() {
+            lines.push(Line::from("No data").centered());
         }
+
+        let sessions = Paragraph::new(lines).block(
+            Block::bordered()
+                .title("Sessions")
+                .border_type(BorderType::Rounded),
+        );
+        frame.render_widget(sessions, area);
+    }
+
+    // Limits of the applications with the time used, the selected one can be removed
+    fn notifications(&mut self, frame: &mut Frame, area: Rect) {
+        let horizontal = Layout::horizontal([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ]);
+        let [_, area, _] = horizontal.areas(area);
+
+        let notifs = self.report(self.database.get_budgets()).unwrap_or_default();
+        self.option_notif.selected = self
+            .option_notif
+            .selected
+            .min(notifs.len().saturating_sub(1));
+
+        let mut lines: Vec<Line> = notifs
+            .iter()
+            .enumerate()
+            .map(|(i, notif)| {
+                if i == self.option_notif.selected {
+                    Line::styled(format!("> {}", notif), (self.color, Modifier::BOLD))
+                } else {
+                    Line::from(format!("  {}", notif))
+                }
+            })
+            .collect();
+
+        if notifs.is_empty() {
+            lines.push(Line::from("No notification").centered());
+        }
+
+        let notifs = Paragraph::new(lines).block(
+            Block::bordered()
+                .title("Notifications")
+                .border_type(BorderType::Rounded),
+        );
+        frame.render_widget(notifs, area);
+    }
+
+    fn get_data(&self) -> Result<Vec<TimeApp>> {
+        let data = match &self.mode {
+            Mode::Day if self.option_day.groups => {
+                self.database.get_time_day_groups(self.option_day.date)?
+            }
+            Mode::Day | Mode::Timeline | Mode::Notif => {
+                self.database.get_time_day(self.option_day.date)?
+            }
+            Mode::App => self.database.get_time_app(
+                &self.option_app.app,
+                self.option_app.date,
+                self.option_app.number,
+            )?,
+        };
+
+        if !self.foreground {
+            return Ok(data);
+        }
+
+        Ok(data
+            .into_iter()
+            .map(|d| TimeApp {
+                month: d.month,
+                ..TimeApp::new(d.name, d.date, d.foreground_total, d.foreground_total)
+            })
+            .collect())
     }
 
     fn is_reverse(&self) -> bool {
         match self.mode {
-            Mode::Day => self.option_day.reverse,
+            Mode::Day | Mode::Timeline | Mode::Notif => self.option_day.reverse,
             Mode::App => self.option_app.reverse,
         }
     }
 
     fn valide_input(&mut self) {
         match self.mode {
-            Mode::Day => {
+            Mode::Day | Mode::Timeline => {
                 if self.show_input == TypeInput::Date {
                     self.option_day.date =
                         NaiveDate::parse_from_str(&self.input.value_and_reset(), "%Y-%m-%d")
@@ -346,19 +626,26 @@ impl App {
                         .parse::<u16>()
                         .unwrap_or(self.option_app.number)
                 }
-                TypeInput::App => {
+                TypeInput::App
                     if self
                         .database
-                        .contain_names(
-                            &String::from_str(self.input.value()).unwrap_or(String::new()),
-                        )
+                        .contain_names(self.input.value())
                         .unwrap_or(false)
-                    {
-                        self.option_app.app = self.input.value_and_reset()
-                    }
+                        || self
+                            .database
+                            .contain_group(self.input.value())
+                            .unwrap_or(false) =>
+                {
+                    self.option_app.app = self.input.value_and_reset()
                 }
                 _ => (),
             },
+            Mode::Notif => {
+                if self.show_input == TypeInput::Notif {
+                    let input = self.input.value_and_reset();
+                    self.add_notif(&input);
+                }
+            }
         }
         self.input.reset();
         self.show_input = TypeInput::None;
@@ -395,7 +682,7 @@ impl App {
             .title("Help")
             .border_type(BorderType::Rounded);
 
-        let vertical = Layout::vertical([Constraint::Length(15)]).flex(Flex::Center);
+        let vertical = Layout::vertical([Constraint::Length(19)]).flex(Flex::Center);
         let horizontal = Layout::horizontal([Constraint::Length(60)]).flex(Flex::Center);
         let [area] = vertical.areas(frame.area());
         let [area] = horizontal.areas(area);
@@ -408,10 +695,14 @@ impl App {
             "<?>".into(),
             "<Q|Esc>".into(),
             "<M>".into(),
+            "<G>".into(),
             "<A>".into(),
             "<D>".into(),
             "<N>".into(),
             "<R>".into(),
+            "<F>".into(),
+            "<S>".into(),
+            "<X|Del>".into(),
             "<Enter>".into(),
             "<T>".into(),
             "<K|Up>".into(),
@@ -425,15 +716,19 @@ impl App {
         let text2: Vec<_> = vec![
             "Show help".into(),
             "Exit application, help, user input".into(),
-            "Switch between day and application mode".into(),
-            "Change the observed application".into(),
+            "Switch between day, application, timeline and notif mode".into(),
+            "Switch between applications and groups".into(),
+            "Change the observed application or group, add a limit".into(),
             "Change observed date".into(),
             "Change the number of days observed".into(),
             "Reverse graph display".into(),
+            "Switch between running and foreground time".into(),
+            "Switch between the graph and the sessions".into(),
+            "Remove the selected limit".into(),
             "Validate user input".into(),
             "Back to today's date".into(),
-            "Increase date by one day".into(),
-            "Reduce date by one day".into(),
+            "Increase date by one day, select the previous limit".into(),
+            "Reduce date by one day, select the next limit".into(),
             "Scroll the graph to the right".into(),
             "Scroll the graph to the left".into(),
         ];
@@ -450,24 +745,29 @@ impl App {
 enum Mode {
     Day,
     App,
+    Timeline,
+    Notif,
 }
 
 struct OptionDay {
     date: NaiveDate,
     reverse: bool,
+    // Displays the groups instead of the applications
+    groups: bool,
     index_bar: usize,
 }
 
 impl OptionDay {
-    fn new() -> OptionDay {
+    fn new(date: NaiveDate) -> OptionDay {
         OptionDay {
-            date: Utc::now().date_naive(),
+            date,
             reverse: false,
+            groups: false,
             index_bar: 0,
         }
     }
 
-    fn render(&self, frame: &mut Frame, area: Rect) {
+    fn render(&self, frame: &mut Frame, area: Rect, mode: &Mode) {
         let horizontal = Layout::horizontal([
             Constraint::Length(2),
             Constraint::Fill(1),
@@ -483,16 +783,34 @@ impl OptionDay {
             Constraint::Length(25),
             Constraint::Length(25),
             Constraint::Length(25),
+            Constraint::Length(25),
             Constraint::Fill(1),
         ]);
-        let [_, mode, date, is_reverse, _] = horizontal.areas(block.inner(area));
+        let [_, mode_area, date, groups, is_reverse, _] = horizontal.areas(block.inner(area));
 
         frame.render_widget(block, area);
-        frame.render_widget(Line::from(" <M>ode : Day ").centered(), mode);
+        let mode_name = match mode {
+            Mode::Timeline => "Timeline",
+            _ => "Day",
+        };
+        frame.render_widget(
+            Line::from(format!(" <M>ode : {} ", mode_name)).centered(),
+            mode_area,
+        );
         frame.render_widget(
             Line::from(format!(" <D>ate : {} {}", self.date.weekday(), self.date)).centered(),
             date,
         );
+
+        // The timeline only depends on the date
+        if *mode == Mode::Timeline {
+            return;
+        }
+
+        frame.render_widget(
+            Line::from(format!(" <G>roups : {} ", self.groups)).centered(),
+            groups,
+        );
         frame.render_widget(
             Line::from(format!(" <R>everse : {} ", self.reverse)).centered(),
             is_reverse,
@@ -502,6 +820,11 @@ impl OptionDay {
     fn switch_reverse(&mut self) {
         self.reverse = !self.reverse;
     }
+
+    fn switch_groups(&mut self) {
+        self.groups = !self.groups;
+        self.index_bar = 0;
+    }
 }
 
 struct OptionApp {
@@ -509,16 +832,19 @@ struct OptionApp {
     date: NaiveDate,
     number: u16,
     reverse: bool,
+    // Displays the sessions of the application instead of the graph
+    sessions: bool,
     index_bar: usize,
 }
 
 impl OptionApp {
-    fn new() -> OptionApp {
+    fn new(date: NaiveDate) -> OptionApp {
         OptionApp {
             app: String::from("pc"),
-            date: Utc::now().date_naive(),
+            date,
             number: 7,
             reverse: false,
+            sessions: false,
             index_bar: 0,
         }
     }
@@ -568,6 +894,48 @@ impl OptionApp {
     fn switch_reverse(&mut self) {
         self.reverse = !self.reverse;
     }
+
+    fn switch_sessions(&mut self) {
+        self.sessions = !self.sessions;
+        self.index_bar = 0;
+    }
+}
+
+struct OptionNotif {
+    // Index of the limit removed with <X>
+    selected: usize,
+}
+
+impl OptionNotif {
+    fn new() -> OptionNotif {
+        OptionNotif { selected: 0 }
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        let horizontal = Layout::horizontal([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ]);
+        let [_, area, _] = horizontal.areas(area);
+
+        let block = Block::bordered()
+            .title_top("Options")
+            .border_type(BorderType::Rounded);
+        let horizontal = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(25),
+            Constraint::Length(25),
+            Constraint::Length(25),
+            Constraint::Fill(1),
+        ]);
+        let [_, mode, add, remove, _] = horizontal.areas(block.inner(area));
+
+        frame.render_widget(block, area);
+        frame.render_widget(Line::from(" <M>ode : Notif ").centered(), mode);
+        frame.render_widget(Line::from(" <A>dd : <app> <minutes> ").centered(), add);
+        frame.render_widget(Line::from(" <X> Remove selected ").centered(), remove);
+    }
 }
 
 #[derive(PartialEq)]
@@ -576,4 +944,6 @@ enum TypeInput {
     Date,
     App,
     Number,
+    // Application and minutes of a new limit
+    Notif,
 }